@@ -1,3 +1,49 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
 fn main() {
     pkg_config::probe_library("x11").unwrap();
-} 
\ No newline at end of file
+    compile_translations();
+}
+
+/// Compile the po/*.po catalogs listed in po/LINGUAS into OUT_DIR/locale
+fn compile_translations() {
+    println!("cargo:rerun-if-changed=po");
+    
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let locale_dir = Path::new(&out_dir).join("locale");
+    println!("cargo:rustc-env=SWIFTLINGO_BUILD_LOCALEDIR={}", locale_dir.display());
+    
+    let linguas = fs::read_to_string("po/LINGUAS").unwrap_or_default();
+    let languages = linguas.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .flat_map(str::split_whitespace);
+    
+    for language in languages {
+        let po_file = Path::new("po").join(format!("{}.po", language));
+        let mo_dir = locale_dir.join(language).join("LC_MESSAGES");
+        
+        if fs::create_dir_all(&mo_dir).is_err() {
+            continue;
+        }
+        
+        let status = Command::new("msgfmt")
+            .arg("--check")
+            .arg("-o")
+            .arg(mo_dir.join("swiftlingo.mo"))
+            .arg(&po_file)
+            .status();
+        
+        match status {
+            Ok(status) if status.success() => {},
+            Ok(_) => println!("cargo:warning=Could not compile {}", po_file.display()),
+            Err(_) => {
+                println!("cargo:warning=msgfmt not found, the interface will not be translated");
+                return;
+            }
+        }
+    }
+}
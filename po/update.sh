@@ -0,0 +1,26 @@
+#!/bin/sh
+# Extract the interface strings into swiftlingo.pot and merge them into
+# the existing translations. Run from anywhere; needs GNU gettext.
+set -e
+
+cd "$(dirname "$0")"
+
+xgettext \
+    --files-from=POTFILES.in \
+    --directory=.. \
+    --output=swiftlingo.pot \
+    --language=C \
+    --from-code=UTF-8 \
+    --keyword=gettext \
+    --keyword=gettext_f \
+    --add-comments=TRANSLATORS \
+    --add-location=file \
+    --package-name=swiftlingo
+
+for lang in $(grep -v '^#' LINGUAS); do
+    if [ -f "$lang.po" ]; then
+        msgmerge --update --backup=none "$lang.po" swiftlingo.pot
+    else
+        msginit --no-translator --locale="$lang" --input=swiftlingo.pot --output-file="$lang.po"
+    fi
+done
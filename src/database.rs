@@ -1,6 +1,9 @@
-use rusqlite::{params, Connection, Result, OptionalExtension};
+use rusqlite::{params, Connection, OptionalExtension, Result, Row};
 use std::env;
 use std::fs;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
 use chrono::Local;
 use serde::{Deserialize, Serialize};
 
@@ -13,6 +16,213 @@ pub struct Translation {
     pub source_lang: String,
     pub target_text: String,
     pub target_lang: String,
+    /// Configuration name of the service that produced the translation
+    pub service: Option<String>,
+    /// Source language detected while "Detect language" was selected
+    pub detected_lang: Option<String>,
+    pub latency_ms: Option<i64>,
+    pub char_count: i64,
+    pub origin: Option<TranslationOrigin>,
+    /// When the translation was last made again, initially `timestamp`
+    pub last_used: String,
+    /// How often the same translation was made
+    pub use_count: i64,
+    /// Kept regardless of the retention limits
+    pub pinned: bool,
+}
+
+/// Where the translated text came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TranslationOrigin {
+    Hotkey,    // Selection sent by the global or in-app hotkey
+    Typed,     // Typed or pasted into the input field
+    Clipboard, // Fetched with the "Get Selection" button
+    Speech,    // Transcribed from the microphone or an audio file
+}
+
+impl TranslationOrigin {
+    pub const ALL: [TranslationOrigin; 4] = [
+        TranslationOrigin::Hotkey,
+        TranslationOrigin::Typed,
+        TranslationOrigin::Clipboard,
+        TranslationOrigin::Speech,
+    ];
+    
+    /// Name stored in the database
+    pub fn name(&self) -> &'static str {
+        match self {
+            TranslationOrigin::Hotkey => "hotkey",
+            TranslationOrigin::Typed => "typed",
+            TranslationOrigin::Clipboard => "clipboard",
+            TranslationOrigin::Speech => "speech",
+        }
+    }
+    
+    pub fn from_name(name: &str) -> Option<TranslationOrigin> {
+        TranslationOrigin::ALL.into_iter().find(|origin| origin.name() == name)
+    }
+}
+
+/// Details recorded with a new history entry
+#[derive(Debug, Clone)]
+pub struct TranslationMetadata {
+    pub service: Option<String>,
+    pub detected_lang: Option<String>,
+    pub latency_ms: Option<i64>,
+    pub origin: TranslationOrigin,
+}
+
+/// Limits on how much history is kept. Pinned entries and entries in a list
+/// are exempt and do not count towards the limits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    pub max_entries: Option<i64>,
+    pub max_age_days: Option<i64>,
+    /// Total size of the source and target texts in bytes
+    pub max_size_bytes: Option<i64>,
+}
+
+impl RetentionPolicy {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_entries.is_none() && self.max_age_days.is_none() && self.max_size_bytes.is_none()
+    }
+}
+
+/// How often the background job applies the retention policy
+const PRUNE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Entries the retention policy may delete, on `translations` aliased as `t`
+const PRUNABLE: &str = "t.pinned = 0 AND t.id NOT IN (SELECT translation_id FROM list_entries)";
+
+/// Order of the history view
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HistorySort {
+    #[default]
+    Recent,   // Most recently used first
+    Frequent, // Most often used first
+}
+
+/// Restricts the history to entries from one service and/or origin and
+/// sets its order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HistoryFilter {
+    pub service: Option<String>,
+    pub origin: Option<TranslationOrigin>,
+    pub sort: HistorySort,
+}
+
+/// Columns read by `Translation::from_row`, on the `translations` table aliased as `t`
+const TRANSLATION_COLUMNS: &str = "t.id, t.timestamp, t.source_text, t.source_lang, t.target_text, t.target_lang,
+    t.service, t.detected_lang, t.latency_ms, t.char_count, t.origin, t.last_used, t.use_count, t.pinned";
+
+/// Number of columns in `TRANSLATION_COLUMNS`
+const TRANSLATION_COLUMN_COUNT: usize = 14;
+
+/// Condition matching a `HistoryFilter` bound as service and origin at the given parameters
+fn filter_condition(service_param: usize, origin_param: usize) -> String {
+    format!(
+        "(?{0} IS NULL OR t.service = ?{0}) AND (?{1} IS NULL OR t.origin = ?{1})",
+        service_param, origin_param
+    )
+}
+
+impl Translation {
+    fn from_row(row: &Row) -> Result<Self> {
+        let origin: Option<String> = row.get(10)?;
+        
+        Ok(Translation {
+            id: row.get(0)?,
+            timestamp: row.get(1)?,
+            source_text: row.get(2)?,
+            source_lang: row.get(3)?,
+            target_text: row.get(4)?,
+            target_lang: row.get(5)?,
+            service: row.get(6)?,
+            detected_lang: row.get(7)?,
+            latency_ms: row.get(8)?,
+            char_count: row.get(9)?,
+            origin: origin.as_deref().and_then(TranslationOrigin::from_name),
+            last_used: row.get(11)?,
+            use_count: row.get(12)?,
+            pinned: row.get(13)?,
+        })
+    }
+}
+
+impl HistoryFilter {
+    fn origin_name(&self) -> Option<&'static str> {
+        self.origin.map(|origin| origin.name())
+    }
+    
+    /// ORDER BY terms for the sort mode
+    fn order_by(&self) -> &'static str {
+        match self.sort {
+            HistorySort::Recent => "t.last_used DESC",
+            HistorySort::Frequent => "t.use_count DESC, t.last_used DESC",
+        }
+    }
+}
+
+/// Source text reduced to what makes two translations the same: trimmed,
+/// with collapsed whitespace and in lowercase
+pub fn normalize_source(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Fill in the normalized source texts and merge translations that only
+/// differ in case or spacing, keeping their list memberships
+fn merge_duplicate_translations(conn: &Connection) -> Result<()> {
+    let mut stmt = conn.prepare("SELECT id, source_text FROM translations")?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>>>()?;
+    
+    for (id, source_text) in rows {
+        conn.execute(
+            "UPDATE translations SET normalized_source = ?1 WHERE id = ?2",
+            params![normalize_source(&source_text), id],
+        )?;
+    }
+    
+    // Keep the oldest entry of each group
+    let mut stmt = conn.prepare(
+        "SELECT t.id, keep.id
+         FROM translations t
+         JOIN translations keep
+           ON keep.normalized_source = t.normalized_source
+          AND keep.source_lang = t.source_lang
+          AND keep.target_lang = t.target_lang
+          AND keep.target_text = t.target_text
+          AND keep.id = (SELECT MIN(id) FROM translations d
+                         WHERE d.normalized_source = t.normalized_source
+                           AND d.source_lang = t.source_lang
+                           AND d.target_lang = t.target_lang
+                           AND d.target_text = t.target_text)
+         WHERE t.id != keep.id"
+    )?;
+    let duplicates = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))?
+        .collect::<Result<Vec<_>>>()?;
+    
+    for (duplicate_id, keep_id) in duplicates {
+        Database::merge_translation(conn, duplicate_id, keep_id)?;
+    }
+    
+    Ok(())
+}
+
+/// Marks the start of a matched term in search snippets
+pub const MATCH_START: &str = "\u{E000}";
+/// Marks the end of a matched term in search snippets
+pub const MATCH_END: &str = "\u{E001}";
+
+/// A history search result with the matching parts of both texts
+#[derive(Debug, Clone)]
+pub struct SearchMatch {
+    pub translation: Translation,
+    pub source_snippet: String,
+    pub target_snippet: String,
 }
 
 /// Represents a translation list (like a playlist)
@@ -38,8 +248,104 @@ pub struct Database {
     conn: Connection,
 }
 
+/// One step of the schema history
+enum Migration {
+    Sql(&'static str),
+    /// For data changes that cannot be expressed in SQL
+    Code(fn(&Connection) -> Result<()>),
+}
+
+/// Schema migrations, applied in order inside a transaction each.
+/// `PRAGMA user_version` holds the number of migrations already applied, so
+/// new migrations must only ever be appended.
+const MIGRATIONS: &[Migration] = &[
+    // 1: Initial schema, already present in databases created before versioning
+    Migration::Sql("CREATE TABLE IF NOT EXISTS translations (
+        id INTEGER PRIMARY KEY,
+        timestamp TEXT NOT NULL,
+        source_text TEXT NOT NULL,
+        source_lang TEXT NOT NULL,
+        target_text TEXT NOT NULL,
+        target_lang TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS lists (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL,
+        created_at TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS list_entries (
+        id INTEGER PRIMARY KEY,
+        list_id INTEGER NOT NULL,
+        translation_id INTEGER NOT NULL,
+        FOREIGN KEY (list_id) REFERENCES lists (id) ON DELETE CASCADE,
+        FOREIGN KEY (translation_id) REFERENCES translations (id) ON DELETE CASCADE
+    );
+    CREATE TABLE IF NOT EXISTS service_usage (
+        service TEXT NOT NULL,
+        month TEXT NOT NULL,
+        characters INTEGER NOT NULL DEFAULT 0,
+        PRIMARY KEY (service, month)
+    );"),
+    
+    // 2: Indexes for history ordering and list lookups. Entries left behind
+    // while foreign keys were not enforced are removed first.
+    Migration::Sql("DELETE FROM list_entries
+        WHERE list_id NOT IN (SELECT id FROM lists)
+           OR translation_id NOT IN (SELECT id FROM translations);
+    CREATE INDEX IF NOT EXISTS idx_translations_timestamp ON translations (timestamp);
+    CREATE INDEX IF NOT EXISTS idx_list_entries_list ON list_entries (list_id);
+    CREATE INDEX IF NOT EXISTS idx_list_entries_translation ON list_entries (translation_id);"),
+    
+    // 3: Full-text index over both texts, kept in sync by triggers
+    Migration::Sql("CREATE VIRTUAL TABLE translations_fts USING fts5(
+        source_text,
+        target_text,
+        content = 'translations',
+        content_rowid = 'id',
+        tokenize = 'porter unicode61 remove_diacritics 2'
+    );
+    CREATE TRIGGER translations_fts_insert AFTER INSERT ON translations BEGIN
+        INSERT INTO translations_fts (rowid, source_text, target_text)
+        VALUES (new.id, new.source_text, new.target_text);
+    END;
+    CREATE TRIGGER translations_fts_delete AFTER DELETE ON translations BEGIN
+        INSERT INTO translations_fts (translations_fts, rowid, source_text, target_text)
+        VALUES ('delete', old.id, old.source_text, old.target_text);
+    END;
+    CREATE TRIGGER translations_fts_update AFTER UPDATE OF source_text, target_text ON translations BEGIN
+        INSERT INTO translations_fts (translations_fts, rowid, source_text, target_text)
+        VALUES ('delete', old.id, old.source_text, old.target_text);
+        INSERT INTO translations_fts (rowid, source_text, target_text)
+        VALUES (new.id, new.source_text, new.target_text);
+    END;
+    INSERT INTO translations_fts (translations_fts) VALUES ('rebuild');"),
+    
+    // 4: Service, detection, latency, size and origin of each translation
+    Migration::Sql("ALTER TABLE translations ADD COLUMN service TEXT;
+    ALTER TABLE translations ADD COLUMN detected_lang TEXT;
+    ALTER TABLE translations ADD COLUMN latency_ms INTEGER;
+    ALTER TABLE translations ADD COLUMN char_count INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE translations ADD COLUMN origin TEXT;
+    UPDATE translations SET char_count = length(source_text);"),
+    
+    // 5: Repeated translations update one entry instead of adding new ones
+    Migration::Sql("ALTER TABLE translations ADD COLUMN normalized_source TEXT;
+    ALTER TABLE translations ADD COLUMN last_used TEXT;
+    ALTER TABLE translations ADD COLUMN use_count INTEGER NOT NULL DEFAULT 1;
+    UPDATE translations SET last_used = timestamp;
+    CREATE INDEX IF NOT EXISTS idx_translations_last_used ON translations (last_used);
+    CREATE INDEX IF NOT EXISTS idx_translations_dedup
+        ON translations (normalized_source, source_lang, target_lang);"),
+    
+    // 6: Normalize existing source texts and merge the duplicates among them
+    Migration::Code(merge_duplicate_translations),
+    
+    // 7: Pinned entries are kept by the retention limits
+    Migration::Sql("ALTER TABLE translations ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;"),
+];
+
 impl Database {
-    /// Create a new database connection and initialize tables if they don't exist
+    /// Create a new database connection and bring the schema up to date
     pub fn new() -> Result<Self> {
         // Create config directory if it doesn't exist
         let home_dir = env::var("HOME").unwrap_or_else(|_| ".".to_string());
@@ -47,60 +353,49 @@ impl Database {
         fs::create_dir_all(&db_dir).map_err(|_| rusqlite::Error::ExecuteReturnedResults)?;
         
         // Connect to database
-        let db_path = format!("{}/translations.db", db_dir);
-        let conn = Connection::open(db_path)?;
+        let mut conn = Database::open_connection()?;
         
-        // Initialize database schema
-        Database::init_schema(&conn)?;
+        // Apply pending schema migrations
+        Database::migrate(&mut conn)?;
         
         Ok(Database { conn })
     }
     
     /// Create a clone by opening a new connection to the same database
     pub fn clone(&self) -> Self {
-        let home_dir = env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        let db_path = format!("{}/.config/translator-app/translations.db", home_dir);
-        
-        let conn = Connection::open(db_path).expect("Failed to clone database connection");
+        let conn = Database::open_connection().expect("Failed to clone database connection");
         Database { conn }
     }
     
-    /// Initialize the database schema if not already created
-    fn init_schema(conn: &Connection) -> Result<()> {
-        // Create translations table
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS translations (
-                id INTEGER PRIMARY KEY,
-                timestamp TEXT NOT NULL,
-                source_text TEXT NOT NULL,
-                source_lang TEXT NOT NULL,
-                target_text TEXT NOT NULL,
-                target_lang TEXT NOT NULL
-            )",
-            [],
-        )?;
+    /// Open a connection to the database file with foreign keys enforced
+    fn open_connection() -> Result<Connection> {
+        let home_dir = env::var("HOME").unwrap_or_else(|_| ".".to_string());
+        let db_path = format!("{}/.config/translator-app/translations.db", home_dir);
         
-        // Create lists table
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS lists (
-                id INTEGER PRIMARY KEY,
-                name TEXT NOT NULL,
-                created_at TEXT NOT NULL
-            )",
-            [],
-        )?;
+        let conn = Connection::open(db_path)?;
         
-        // Create list_entries table for many-to-many relationship
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS list_entries (
-                id INTEGER PRIMARY KEY,
-                list_id INTEGER NOT NULL,
-                translation_id INTEGER NOT NULL,
-                FOREIGN KEY (list_id) REFERENCES lists (id) ON DELETE CASCADE,
-                FOREIGN KEY (translation_id) REFERENCES translations (id) ON DELETE CASCADE
-            )",
-            [],
-        )?;
+        // Foreign keys are off by default and must be enabled per connection
+        conn.pragma_update(None, "foreign_keys", true)?;
+        
+        // Wait for the other connections, such as the prune job, instead of failing
+        conn.busy_timeout(Duration::from_secs(5))?;
+        
+        Ok(conn)
+    }
+    
+    /// Apply the migrations not yet recorded in `PRAGMA user_version`
+    fn migrate(conn: &mut Connection) -> Result<()> {
+        let version: usize = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            let tx = conn.transaction()?;
+            match migration {
+                Migration::Sql(sql) => tx.execute_batch(sql)?,
+                Migration::Code(apply) => apply(&tx)?,
+            }
+            tx.pragma_update(None, "user_version", index + 1)?;
+            tx.commit()?;
+        }
         
         Ok(())
     }
@@ -112,38 +407,144 @@ impl Database {
     }
     
     /// Add a translation to history
+    ///
+    /// A translation already in the history with the same normalized source
+    /// text, languages and target is marked as used again instead, and its ID
+    /// is returned.
     pub fn add_translation(&self, source_text: &str, source_lang: &str, 
-                          target_text: &str, target_lang: &str) -> Result<i64> {
+                          target_text: &str, target_lang: &str,
+                          metadata: &TranslationMetadata) -> Result<i64> {
+        match self.find_translation(source_text, source_lang, target_text, target_lang)? {
+            Some(id) => {
+                self.conn.execute(
+                    "UPDATE translations SET last_used = ?1, use_count = use_count + 1 WHERE id = ?2",
+                    params![Database::current_timestamp(), id],
+                )?;
+                
+                Ok(id)
+            },
+            None => self.insert_translation(source_text, source_lang, target_text, target_lang, metadata),
+        }
+    }
+    
+    /// Get the ID of a translation in the history, adding it if it is missing.
+    ///
+    /// Unlike `add_translation` this does not count as a use, e.g. when an
+    /// existing translation is only saved to a list.
+    pub fn find_or_add_translation(&self, source_text: &str, source_lang: &str,
+                                   target_text: &str, target_lang: &str,
+                                   metadata: &TranslationMetadata) -> Result<i64> {
+        match self.find_translation(source_text, source_lang, target_text, target_lang)? {
+            Some(id) => Ok(id),
+            None => self.insert_translation(source_text, source_lang, target_text, target_lang, metadata),
+        }
+    }
+    
+    /// Find the history entry with the same normalized source text, languages and target
+    fn find_translation(&self, source_text: &str, source_lang: &str,
+                        target_text: &str, target_lang: &str) -> Result<Option<i64>> {
+        self.conn.query_row(
+            "SELECT id FROM translations
+             WHERE normalized_source = ?1 AND source_lang = ?2 AND target_lang = ?3 AND target_text = ?4",
+            params![normalize_source(source_text), source_lang, target_lang, target_text],
+            |row| row.get(0),
+        ).optional()
+    }
+    
+    /// Insert a new history entry used once
+    fn insert_translation(&self, source_text: &str, source_lang: &str,
+                          target_text: &str, target_lang: &str,
+                          metadata: &TranslationMetadata) -> Result<i64> {
         let timestamp = Database::current_timestamp();
+        let char_count = source_text.chars().count() as i64;
         
         self.conn.execute(
-            "INSERT INTO translations (timestamp, source_text, source_lang, target_text, target_lang)
-             VALUES (?1, ?2, ?3, ?4, ?5)",
-            params![timestamp, source_text, source_lang, target_text, target_lang],
+            "INSERT INTO translations (timestamp, source_text, source_lang, target_text, target_lang,
+                                       service, detected_lang, latency_ms, char_count, origin,
+                                       normalized_source, last_used)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?1)",
+            params![
+                timestamp, source_text, source_lang, target_text, target_lang,
+                metadata.service, metadata.detected_lang, metadata.latency_ms, char_count, metadata.origin.name(),
+                normalize_source(source_text)
+            ],
         )?;
         
         Ok(self.conn.last_insert_rowid())
     }
     
-    /// Get all translations (for history view)
-    pub fn get_translations(&self, limit: i64) -> Result<Vec<Translation>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, timestamp, source_text, source_lang, target_text, target_lang
-             FROM translations 
-             ORDER BY timestamp DESC
-             LIMIT ?1"
+    /// Fold a duplicate translation into another one: add up the use counts,
+    /// keep the latest use and move its list memberships over
+    fn merge_translation(conn: &Connection, duplicate_id: i64, keep_id: i64) -> Result<()> {
+        conn.execute(
+            "UPDATE translations
+             SET use_count = use_count + (SELECT use_count FROM translations WHERE id = ?1),
+                 last_used = MAX(last_used, (SELECT last_used FROM translations WHERE id = ?1))
+             WHERE id = ?2",
+            params![duplicate_id, keep_id],
         )?;
         
-        let translations = stmt.query_map(params![limit], |row| {
-            Ok(Translation {
-                id: row.get(0)?,
-                timestamp: row.get(1)?,
-                source_text: row.get(2)?,
-                source_lang: row.get(3)?,
-                target_text: row.get(4)?,
-                target_lang: row.get(5)?,
-            })
-        })?;
+        // Entries for lists that already hold the kept translation are dropped with the duplicate
+        conn.execute(
+            "UPDATE list_entries SET translation_id = ?2
+             WHERE translation_id = ?1
+               AND list_id NOT IN (SELECT list_id FROM list_entries WHERE translation_id = ?2)",
+            params![duplicate_id, keep_id],
+        )?;
+        
+        conn.execute("DELETE FROM translations WHERE id = ?1", params![duplicate_id])?;
+        
+        Ok(())
+    }
+    
+    /// Add billed characters to a service's usage for the given month
+    pub fn record_usage(&self, service: &str, month: &str, characters: i64) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO service_usage (service, month, characters) VALUES (?1, ?2, ?3)
+             ON CONFLICT (service, month) DO UPDATE SET characters = characters + excluded.characters",
+            params![service, month, characters],
+        )?;
+        
+        Ok(())
+    }
+    
+    /// Raise a service's usage to the count reported by the service itself
+    pub fn sync_reported_usage(&self, service: &str, month: &str, characters: i64) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO service_usage (service, month, characters) VALUES (?1, ?2, ?3)
+             ON CONFLICT (service, month) DO UPDATE SET characters = MAX(characters, excluded.characters)",
+            params![service, month, characters],
+        )?;
+        
+        Ok(())
+    }
+    
+    /// Get the characters billed by a service in the given month
+    pub fn get_usage(&self, service: &str, month: &str) -> Result<i64> {
+        let characters = self.conn.query_row(
+            "SELECT characters FROM service_usage WHERE service = ?1 AND month = ?2",
+            params![service, month],
+            |row| row.get(0),
+        ).optional()?;
+        
+        Ok(characters.unwrap_or(0))
+    }
+    
+    /// Get the most recent translations matching a filter (for history view)
+    pub fn get_translations(&self, limit: i64, filter: &HistoryFilter) -> Result<Vec<Translation>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}
+             FROM translations t
+             WHERE {}
+             ORDER BY {}
+             LIMIT ?1",
+            TRANSLATION_COLUMNS, filter_condition(2, 3), filter.order_by()
+        ))?;
+        
+        let translations = stmt.query_map(
+            params![limit, filter.service, filter.origin_name()],
+            Translation::from_row
+        )?;
         
         let mut result = Vec::new();
         for translation in translations {
@@ -216,27 +617,22 @@ impl Database {
         Ok(self.conn.last_insert_rowid())
     }
     
-    /// Get translations in a specific list
-    pub fn get_list_translations(&self, list_id: i64) -> Result<Vec<Translation>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT t.id, t.timestamp, t.source_text, t.source_lang, t.target_text, t.target_lang
+    /// Get translations in a specific list matching a filter
+    pub fn get_list_translations(&self, list_id: i64, filter: &HistoryFilter) -> Result<Vec<Translation>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}
              FROM translations t
              JOIN list_entries le ON t.id = le.translation_id
-             WHERE le.list_id = ?1
-             ORDER BY t.timestamp DESC"
+             WHERE le.list_id = ?1 AND {}
+             ORDER BY {}",
+            TRANSLATION_COLUMNS, filter_condition(2, 3), filter.order_by()
+        ))?;
+        
+        let translations = stmt.query_map(
+            params![list_id, filter.service, filter.origin_name()],
+            Translation::from_row
         )?;
         
-        let translations = stmt.query_map(params![list_id], |row| {
-            Ok(Translation {
-                id: row.get(0)?,
-                timestamp: row.get(1)?,
-                source_text: row.get(2)?,
-                source_lang: row.get(3)?,
-                target_text: row.get(4)?,
-                target_lang: row.get(5)?,
-            })
-        })?;
-        
         let mut result = Vec::new();
         for translation in translations {
             result.push(translation?);
@@ -267,15 +663,8 @@ impl Database {
         Ok(count > 0)
     }
     
-    /// Delete a list and its entries
+    /// Delete a list; its entries are removed by the foreign key cascade
     pub fn delete_list(&self, list_id: i64) -> Result<()> {
-        // Delete entries first (should cascade, but just to be safe)
-        self.conn.execute(
-            "DELETE FROM list_entries WHERE list_id = ?1",
-            params![list_id],
-        )?;
-        
-        // Delete the list
         self.conn.execute(
             "DELETE FROM lists WHERE id = ?1",
             params![list_id],
@@ -295,48 +684,147 @@ impl Database {
         Ok(())
     }
     
-    /// Delete a translation from history and all lists
+    /// Delete a translation from history; the foreign key cascade removes it from all lists
     pub fn delete_translation(&self, translation_id: i64) -> Result<()> {
-        // Delete entries first
         self.conn.execute(
-            "DELETE FROM list_entries WHERE translation_id = ?1",
+            "DELETE FROM translations WHERE id = ?1",
             params![translation_id],
         )?;
         
-        // Delete the translation
+        Ok(())
+    }
+    
+    /// Pin or unpin a translation, exempting it from the retention limits
+    pub fn set_pinned(&self, translation_id: i64, pinned: bool) -> Result<()> {
         self.conn.execute(
-            "DELETE FROM translations WHERE id = ?1",
-            params![translation_id],
+            "UPDATE translations SET pinned = ?1 WHERE id = ?2",
+            params![pinned, translation_id],
         )?;
         
         Ok(())
     }
     
-    /// Search translations by text
-    pub fn search_translations(&self, query: &str) -> Result<Vec<Translation>> {
-        let search_query = format!("%{}%", query);
+    /// Delete the history entries that exceed the retention limits, least
+    /// recently used first. Returns the number of deleted entries.
+    pub fn prune(&self, policy: &RetentionPolicy) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut deleted = 0;
+        
+        // Step 1: Entries not used within the age limit
+        if let Some(days) = policy.max_age_days {
+            let cutoff = (Local::now() - chrono::Duration::days(days)).to_rfc3339();
+            deleted += tx.execute(
+                &format!("DELETE FROM translations WHERE id IN (
+                    SELECT t.id FROM translations t WHERE {} AND t.last_used < ?1
+                 )", PRUNABLE),
+                params![cutoff],
+            )?;
+        }
         
-        let mut stmt = self.conn.prepare(
-            "SELECT id, timestamp, source_text, source_lang, target_text, target_lang
-             FROM translations 
-             WHERE source_text LIKE ?1 OR target_text LIKE ?1
-             ORDER BY timestamp DESC"
-        )?;
+        // Step 2: Entries beyond the most recently used ones
+        if let Some(max_entries) = policy.max_entries {
+            deleted += tx.execute(
+                &format!("DELETE FROM translations WHERE id IN (
+                    SELECT t.id FROM translations t WHERE {}
+                    ORDER BY t.last_used DESC
+                    LIMIT -1 OFFSET ?1
+                 )", PRUNABLE),
+                params![max_entries],
+            )?;
+        }
         
-        let translations = stmt.query_map(params![search_query], |row| {
-            Ok(Translation {
-                id: row.get(0)?,
-                timestamp: row.get(1)?,
-                source_text: row.get(2)?,
-                source_lang: row.get(3)?,
-                target_text: row.get(4)?,
-                target_lang: row.get(5)?,
+        // Step 3: Oldest entries until the remaining texts fit the size limit
+        if let Some(max_size) = policy.max_size_bytes {
+            deleted += tx.execute(
+                &format!("DELETE FROM translations WHERE id IN (
+                    SELECT id FROM (
+                        SELECT t.id, SUM(length(CAST(t.source_text AS BLOB)) + length(CAST(t.target_text AS BLOB)))
+                            OVER (ORDER BY t.last_used DESC, t.id DESC) AS total_size
+                        FROM translations t WHERE {}
+                    ) WHERE total_size > ?1
+                 )", PRUNABLE),
+                params![max_size],
+            )?;
+        }
+        
+        tx.commit()?;
+        
+        Ok(deleted)
+    }
+    
+    /// Prune the history on a background thread, now and then every few
+    /// minutes, with the policy currently in `policy`
+    pub fn spawn_prune_job(policy: Arc<Mutex<RetentionPolicy>>) {
+        thread::spawn(move || {
+            let db = match Database::new() {
+                Ok(db) => db,
+                Err(e) => {
+                    eprintln!("History pruning disabled, could not open the database: {}", e);
+                    return;
+                }
+            };
+            
+            loop {
+                let current = *policy.lock().unwrap();
+                if !current.is_unlimited() {
+                    match db.prune(&current) {
+                        Ok(0) => {},
+                        Ok(deleted) => println!("Pruned {} history entries", deleted),
+                        Err(e) => eprintln!("Failed to prune history: {}", e),
+                    }
+                }
+                
+                thread::sleep(PRUNE_INTERVAL);
+            }
+        });
+    }
+    
+    /// Full-text search of the history, best matches first
+    ///
+    /// Supports `word*` prefixes, `"quoted phrases"` and AND/OR/NOT with
+    /// parentheses; the last word is also matched as a prefix while typing.
+    /// Matched terms in the snippets are wrapped in `MATCH_START`/`MATCH_END`.
+    pub fn search_translations(&self, query: &str, limit: i64, filter: &HistoryFilter) -> Result<Vec<SearchMatch>> {
+        let fts_query = match fts_query(query) {
+            Some(fts_query) => fts_query,
+            None => return Ok(Vec::new()),
+        };
+        
+        // Fall back to plain terms when the query is not valid FTS5 syntax
+        match self.query_fts(&fts_query, limit, filter) {
+            Err(rusqlite::Error::SqliteFailure(_, _)) => match fts_terms(query) {
+                Some(terms) => self.query_fts(&terms, limit, filter),
+                None => Ok(Vec::new()),
+            },
+            result => result,
+        }
+    }
+    
+    fn query_fts(&self, fts_query: &str, limit: i64, filter: &HistoryFilter) -> Result<Vec<SearchMatch>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {},
+                    snippet(translations_fts, 0, ?2, ?3, '…', 16),
+                    snippet(translations_fts, 1, ?2, ?3, '…', 16)
+             FROM translations_fts
+             JOIN translations t ON t.id = translations_fts.rowid
+             WHERE translations_fts MATCH ?1 AND {}
+             ORDER BY rank
+             LIMIT ?4",
+            TRANSLATION_COLUMNS, filter_condition(5, 6)
+        ))?;
+        
+        let params = params![fts_query, MATCH_START, MATCH_END, limit, filter.service, filter.origin_name()];
+        let matches = stmt.query_map(params, |row| {
+            Ok(SearchMatch {
+                translation: Translation::from_row(row)?,
+                source_snippet: row.get(TRANSLATION_COLUMN_COUNT)?,
+                target_snippet: row.get(TRANSLATION_COLUMN_COUNT + 1)?,
             })
         })?;
         
         let mut result = Vec::new();
-        for translation in translations {
-            result.push(translation?);
+        for search_match in matches {
+            result.push(search_match?);
         }
         
         Ok(result)
@@ -344,7 +832,7 @@ impl Database {
     
     /// Export a list to Anki-compatible format
     pub fn export_list_for_anki(&self, list_id: i64) -> Result<String> {
-        let translations = self.get_list_translations(list_id)?;
+        let translations = self.get_list_translations(list_id, &HistoryFilter::default())?;
         
         // Format: source text;target text;source language;target language
         let mut csv = String::from("source;target;source_lang;target_lang\n");
@@ -364,25 +852,162 @@ impl Database {
     }
     
     /// Get a translation by ID
-    #[allow(dead_code)]
     pub fn get_translation_by_id(&self, translation_id: i64) -> Result<Option<Translation>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, timestamp, source_text, source_lang, target_text, target_lang
-             FROM translations 
-             WHERE id = ?1"
-        )?;
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM translations t WHERE t.id = ?1",
+            TRANSLATION_COLUMNS
+        ))?;
         
-        let translation = stmt.query_row(params![translation_id], |row| {
-            Ok(Translation {
-                id: row.get(0)?,
-                timestamp: row.get(1)?,
-                source_text: row.get(2)?,
-                source_lang: row.get(3)?,
-                target_text: row.get(4)?,
-                target_lang: row.get(5)?,
-            })
-        }).optional()?;
+        let translation = stmt.query_row(params![translation_id], Translation::from_row).optional()?;
         
         Ok(translation)
     }
-}
\ No newline at end of file
+}
+
+/// One piece of a search query
+#[derive(Debug, Clone, PartialEq)]
+enum QueryToken {
+    Term(String),
+    Operator(&'static str),
+    Open,
+    Close,
+}
+
+/// Turn a search box query into FTS5 syntax
+///
+/// Words and phrases are quoted so punctuation cannot break the query,
+/// `*` suffixes become prefix searches and AND/OR/NOT stay operators.
+/// Operators without an operand on both sides and unbalanced parentheses
+/// are dropped.
+fn fts_query(input: &str) -> Option<String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    
+    while let Some(&c) = chars.peek() {
+        match c {
+            '"' => {
+                chars.next();
+                let phrase: String = chars.by_ref().take_while(|&c| c != '"').collect();
+                let prefix = chars.next_if_eq(&'*').is_some();
+                if !phrase.trim().is_empty() {
+                    tokens.push(QueryToken::Term(quote_term(&phrase, prefix)));
+                }
+            }
+            '(' => {
+                chars.next();
+                tokens.push(QueryToken::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(QueryToken::Close);
+            }
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(c) = chars.next_if(|&c| !c.is_whitespace() && !matches!(c, '"' | '(' | ')')) {
+                    word.push(c);
+                }
+                
+                match word.as_str() {
+                    "AND" => tokens.push(QueryToken::Operator("AND")),
+                    "OR" => tokens.push(QueryToken::Operator("OR")),
+                    "NOT" => tokens.push(QueryToken::Operator("NOT")),
+                    _ => {
+                        // Match the word being typed as a prefix
+                        let typing = chars.peek().is_none();
+                        let prefix = word.ends_with('*') || typing;
+                        let word = word.trim_end_matches('*');
+                        if !word.is_empty() {
+                            tokens.push(QueryToken::Term(quote_term(word, prefix)));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    
+    let mut query: Vec<QueryToken> = Vec::new();
+    let mut depth = 0;
+    
+    for token in tokens {
+        match token {
+            QueryToken::Operator(_) => {
+                if matches!(query.last(), Some(QueryToken::Term(_)) | Some(QueryToken::Close)) {
+                    query.push(token);
+                }
+            }
+            QueryToken::Close => {
+                if matches!(query.last(), Some(QueryToken::Operator(_))) {
+                    query.pop();
+                }
+                if depth == 0 {
+                    continue;
+                }
+                if query.last() == Some(&QueryToken::Open) {
+                    // Empty group
+                    query.pop();
+                } else {
+                    query.push(QueryToken::Close);
+                }
+                depth -= 1;
+            }
+            QueryToken::Open => {
+                depth += 1;
+                query.push(token);
+            }
+            QueryToken::Term(_) => query.push(token),
+        }
+    }
+    
+    while depth > 0 {
+        while matches!(query.last(), Some(QueryToken::Operator(_))) {
+            query.pop();
+        }
+        if query.last() == Some(&QueryToken::Open) {
+            query.pop();
+        } else {
+            query.push(QueryToken::Close);
+        }
+        depth -= 1;
+    }
+    while matches!(query.last(), Some(QueryToken::Operator(_))) {
+        query.pop();
+    }
+    
+    if !query.iter().any(|token| matches!(token, QueryToken::Term(_))) {
+        return None;
+    }
+    
+    let parts: Vec<&str> = query.iter()
+        .map(|token| match token {
+            QueryToken::Term(term) => term.as_str(),
+            QueryToken::Operator(operator) => operator,
+            QueryToken::Open => "(",
+            QueryToken::Close => ")",
+        })
+        .collect();
+    
+    Some(parts.join(" "))
+}
+
+/// All words of a query as plain terms that must all match
+fn fts_terms(input: &str) -> Option<String> {
+    let terms: Vec<String> = input.split(|c: char| c.is_whitespace() || matches!(c, '"' | '(' | ')' | '*'))
+        .filter(|word| !word.is_empty())
+        .map(|word| quote_term(word, false))
+        .collect();
+    
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+/// Quote a word or phrase for FTS5, optionally as a prefix search
+fn quote_term(term: &str, prefix: bool) -> String {
+    let quoted = format!("\"{}\"", term.replace('"', "\"\""));
+    if prefix {
+        quoted + "*"
+    } else {
+        quoted
+    }
+}
@@ -0,0 +1,351 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use once_cell::sync::Lazy;
+use crate::languages;
+
+/// Guesses below this confidence are shown but not acted on
+pub const MIN_CONFIDENCE: f64 = 0.5;
+
+/// Only the start of long texts is examined
+const MAX_DETECTION_CHARS: usize = 1000;
+
+/// Input trigrams needed before a Latin-script guess gets full confidence
+const FULL_CONFIDENCE_TRIGRAMS: usize = 20;
+
+/// A guess at the language of a text
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Detection {
+    pub language: &'static str,
+    pub confidence: f64,
+}
+
+impl Detection {
+    /// Whether the guess is good enough to use instead of "auto"
+    pub fn is_confident(&self) -> bool {
+        self.confidence >= MIN_CONFIDENCE
+    }
+    
+    /// Short description for the UI, e.g. "German (92%)"
+    pub fn summary(&self) -> String {
+        format!("{} ({:.0}%)", languages::display_name(self.language), self.confidence * 100.0)
+    }
+}
+
+/// Writing systems told apart by Unicode block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Script {
+    Latin,
+    Cyrillic,
+    Greek,
+    Armenian,
+    Hebrew,
+    Arabic,
+    Devanagari,
+    Bengali,
+    Gurmukhi,
+    Gujarati,
+    Oriya,
+    Tamil,
+    Telugu,
+    Kannada,
+    Malayalam,
+    Sinhala,
+    Thai,
+    Lao,
+    Myanmar,
+    Georgian,
+    Ethiopic,
+    Khmer,
+    Hangul,
+    Kana,
+    Han,
+}
+
+/// Sample text for each Latin-script language (article 1 of the Universal
+/// Declaration of Human Rights and common words), from which the trigram
+/// profiles are built
+const LATIN_SAMPLES: &[(&str, &str)] = &[
+    ("en", "All human beings are born free and equal in dignity and rights. They are endowed with reason and conscience and should act towards one another in a spirit of brotherhood. the of and to in is that it was for on with as you this have not but what which there when would know"),
+    ("de", "Alle Menschen sind frei und gleich an Würde und Rechten geboren. Sie sind mit Vernunft und Gewissen begabt und sollen einander im Geist der Brüderlichkeit begegnen. der die das und ist nicht ich ein eine zu mit auch auf für sich dem den von wie wir"),
+    ("fr", "Tous les êtres humains naissent libres et égaux en dignité et en droits. Ils sont doués de raison et de conscience et doivent agir les uns envers les autres dans un esprit de fraternité. le la les des est pas que qui une pour avec dans sur vous nous ce je merci bonjour où suis veux bien très"),
+    ("es", "Todos los seres humanos nacen libres e iguales en dignidad y derechos y, dotados como están de razón y conciencia, deben comportarse fraternalmente los unos con los otros. el la que de en los por para una con del se las es muy pero cómo qué hola gracias dónde está estoy quiero bien"),
+    ("it", "Tutti gli esseri umani nascono liberi ed eguali in dignità e diritti. Essi sono dotati di ragione e di coscienza e devono agire gli uni verso gli altri in spirito di fratellanza. il che non per una sono della questo anche come più ma ci mi"),
+    ("pt", "Todos os seres humanos nascem livres e iguais em dignidade e em direitos. Dotados de razão e de consciência, devem agir uns para com os outros em espírito de fraternidade. o que não uma com por mais como mas você está são também isso onde fica obrigado estou sei quero dizer muito bem"),
+    ("nl", "Alle mensen worden vrij en gelijk in waardigheid en rechten geboren. Zij zijn begiftigd met verstand en geweten, en behoren zich jegens elkander in een geest van broederschap te gedragen. de het een is niet dat ik wat op voor ook maar hij"),
+    ("sv", "Alla människor är födda fria och lika i värde och rättigheter. De har utrustats med förnuft och samvete och bör handla gentemot varandra i en anda av broderskap. jag det att som inte på för till den har det är vi"),
+    ("da", "Alle mennesker er født frie og lige i værdighed og rettigheder. De er udstyret med fornuft og samvittighed, og de bør handle mod hverandre i en broderskabets ånd. jeg det at som ikke på for til den har vi hvad også"),
+    ("no", "Alle mennesker er født frie og med samme menneskeverd og menneskerettigheter. De er utstyrt med fornuft og samvittighet og bør handle mot hverandre i brorskapets ånd. jeg det at som ikke på for til den har vi hva også ikkje"),
+    ("fi", "Kaikki ihmiset syntyvät vapaina ja tasavertaisina arvoltaan ja oikeuksiltaan. Heille on annettu järki ja omatunto, ja heidän on toimittava toisiaan kohtaan veljeyden hengessä. ja on ei se että hän mutta kun minä sinä mitä tämä kiitos paljon hyvää huomenta anteeksi kyllä"),
+    ("pl", "Wszyscy ludzie rodzą się wolni i równi pod względem swej godności i swych praw. Są oni obdarzeni rozumem i sumieniem i powinni postępować wobec innych w duchu braterstwa. nie jest to że się na jak ale co tak jestem już"),
+    ("cs", "Všichni lidé rodí se svobodní a sobě rovní co do důstojnosti a práv. Jsou nadáni rozumem a svědomím a mají spolu jednat v duchu bratrství. je to že se na jak ale co tak jsem už není když"),
+    ("sk", "Všetci ľudia sa rodia slobodní a sebe rovní, čo sa týka ich dôstojnosti a práv. Sú obdarení rozumom a majú navzájom jednať v bratskom duchu. je to že sa na ako ale čo tak som už nie keď"),
+    ("hr", "Sva ljudska bića rađaju se slobodna i jednaka u dostojanstvu i pravima. Ona su obdarena razumom i sviješću pa jedna prema drugima trebaju postupati u duhu bratstva. je da se na za što ali kao sam nije ovo"),
+    ("sl", "Vsi ljudje se rodijo svobodni in imajo enako dostojanstvo in enake pravice. Obdarjeni so z razumom in vestjo in bi morali ravnati drug z drugim kakor bratje. je da se na za kaj ampak kot sem ni to"),
+    ("tr", "Bütün insanlar hür, haysiyet ve haklar bakımından eşit doğarlar. Akıl ve vicdana sahiptirler ve birbirlerine karşı kardeşlik zihniyeti ile hareket etmelidirler. bir bu ve da de için ne ama çok gibi daha ben sen o var yok değil mi musun ne demek istiyorum biliyorum teşekkür ederim nasılsın"),
+    ("ro", "Toate ființele umane se nasc libere și egale în demnitate și în drepturi. Ele sunt înzestrate cu rațiune și conștiință și trebuie să se comporte unele față de altele în spiritul fraternității. este nu că pe cu pentru mai dar"),
+    ("hu", "Minden emberi lény szabadon születik és egyenlő méltósága és joga van. Az emberek, ésszel és lelkiismerettel bírván, egymással szemben testvéri szellemben kell hogy viseltessenek. a az nem hogy is de egy meg csak már vagyok van volt lesz ez azt mit köszönöm nagyon jó tudom"),
+    ("id", "Semua orang dilahirkan merdeka dan mempunyai martabat dan hak-hak yang sama. Mereka dikaruniai akal dan hati nurani dan hendaknya bergaul satu sama lain dalam semangat persaudaraan. yang itu ini tidak dengan untuk ada saya akan terima kasih banyak selamat pagi apa kabar bisa"),
+    ("vi", "Tất cả mọi người sinh ra đều được tự do và bình đẳng về nhân phẩm và quyền lợi. Mọi con người đều được tạo hóa ban cho lý trí và lương tâm và cần phải đối xử với nhau trong tình bằng hữu. là của có không một những này"),
+    ("ca", "Tots els éssers humans neixen lliures i iguals en dignitat i en drets. Són dotats de raó i de consciència, i han de comportar-se fraternalment els uns amb els altres. el la que per amb no una però més com això"),
+];
+
+/// Letters that only occur in one of the Latin-script languages above
+const DISTINCTIVE_LETTERS: &[(&str, &str)] = &[
+    ("de", "ß"),
+    ("es", "ñ¿¡"),
+    ("pt", "ãõ"),
+    ("fr", "œ"),
+    ("tr", "ığş"),
+    ("ro", "șț"),
+    ("hu", "őű"),
+    ("pl", "łąęśźżń"),
+    ("cs", "řěů"),
+    ("sk", "ľĺŕ"),
+    ("hr", "đ"),
+    ("vi", "ơưạảấầẩẫậắằẳẵặẹẻẽếềểễệỉịọỏốồổỗộớờởỡợụủứừửữựỳỵỷỹ"),
+];
+
+/// Extra trigram hits a distinctive letter is worth
+const DISTINCTIVE_LETTER_WEIGHT: usize = 3;
+
+/// Trigrams of each Latin-script language
+static LATIN_PROFILES: Lazy<Vec<(&'static str, HashSet<String>)>> = Lazy::new(|| {
+    LATIN_SAMPLES.iter()
+        .map(|(language, sample)| (*language, trigrams(sample).into_iter().collect()))
+        .collect()
+});
+
+/// Guess the language of a text from its script and, for Latin-script
+/// text, its character trigrams. Returns `None` for text without letters.
+pub fn detect(text: &str) -> Option<Detection> {
+    let text: String = text.chars().take(MAX_DETECTION_CHARS).collect();
+    
+    // Step 1: find the dominant script
+    let mut script_counts: HashMap<Script, usize> = HashMap::new();
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        if let Some(script) = script_of(c) {
+            *script_counts.entry(script).or_insert(0) += 1;
+        }
+    }
+    
+    let letters: usize = script_counts.values().sum();
+    if letters == 0 {
+        return None;
+    }
+    
+    // Japanese mixes kana with Han characters
+    let kana = script_counts.remove(&Script::Kana).unwrap_or(0);
+    if kana > 0 {
+        *script_counts.entry(Script::Han).or_insert(0) += kana;
+    }
+    
+    let (script, count) = script_counts.into_iter().max_by_key(|(_, count)| *count)?;
+    let share = count as f64 / letters as f64;
+    
+    // Step 2: pick a language within the script
+    let (language, certainty) = match script {
+        Script::Latin => return detect_latin(&text, share),
+        Script::Cyrillic => cyrillic_language(&text),
+        Script::Arabic => arabic_language(&text),
+        Script::Devanagari => devanagari_language(&text),
+        Script::Hebrew => if text.contains(['ײ', 'װ', 'ױ']) { ("yi", 0.95) } else { ("he", 0.9) },
+        Script::Han if kana > 0 => ("ja", 1.0),
+        Script::Han => chinese_variant(&text),
+        Script::Greek => ("el", 1.0),
+        Script::Armenian => ("hy", 1.0),
+        Script::Bengali => ("bn", 1.0),
+        Script::Gurmukhi => ("pa", 1.0),
+        Script::Gujarati => ("gu", 1.0),
+        Script::Oriya => ("or", 1.0),
+        Script::Tamil => ("ta", 1.0),
+        Script::Telugu => ("te", 1.0),
+        Script::Kannada => ("kn", 1.0),
+        Script::Malayalam => ("ml", 1.0),
+        Script::Sinhala => ("si", 1.0),
+        Script::Thai => ("th", 1.0),
+        Script::Lao => ("lo", 1.0),
+        Script::Myanmar => ("my", 1.0),
+        Script::Georgian => ("ka", 1.0),
+        Script::Ethiopic => ("am", 1.0),
+        Script::Khmer => ("km", 1.0),
+        Script::Hangul => ("ko", 1.0),
+        Script::Kana => ("ja", 1.0),
+    };
+    
+    Some(Detection {
+        language,
+        confidence: share * certainty,
+    })
+}
+
+/// Replace "auto" with the detected language when the guess is confident,
+/// otherwise return the source language unchanged
+pub fn resolve_auto<'a>(text: &str, source_lang: &'a str) -> &'a str {
+    if source_lang != languages::AUTO {
+        return source_lang;
+    }
+    
+    detect(text)
+        .filter(Detection::is_confident)
+        .map_or(source_lang, |detection| detection.language)
+}
+
+/// Pick the Latin-script language whose profile shares the most trigrams
+/// with the text
+fn detect_latin(text: &str, share: f64) -> Option<Detection> {
+    let input = trigrams(text);
+    if input.is_empty() {
+        return None;
+    }
+    
+    let lowercase = text.to_lowercase();
+    let mut scores: Vec<(&'static str, usize)> = LATIN_PROFILES.iter()
+        .map(|(language, profile)| {
+            let hits = input.iter().filter(|trigram| profile.contains(*trigram)).count();
+            let distinctive = DISTINCTIVE_LETTERS.iter()
+                .filter(|(letter_language, _)| letter_language == language)
+                .map(|(_, letters)| lowercase.chars().filter(|c| letters.contains(*c)).count())
+                .sum::<usize>();
+            (*language, hits + distinctive * DISTINCTIVE_LETTER_WEIGHT)
+        })
+        .collect();
+    scores.sort_by_key(|(_, score)| Reverse(*score));
+    
+    let (language, best) = scores[0];
+    let second = scores.get(1).map_or(0, |(_, score)| *score);
+    if best == 0 {
+        return None;
+    }
+    
+    // Confidence grows with the lead over the runner-up and with the
+    // amount of text, since a few trigrams say little
+    let margin = (best - second) as f64 / best as f64;
+    let length = (input.len() as f64 / FULL_CONFIDENCE_TRIGRAMS as f64).min(1.0);
+    
+    Some(Detection {
+        language,
+        confidence: share * length * (0.4 + margin * 2.0).min(1.0),
+    })
+}
+
+/// Character trigrams of each word, padded with spaces
+fn trigrams(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|word| !word.is_empty())
+        .flat_map(|word| {
+            let padded: Vec<char> = format!(" {} ", word).chars().collect();
+            padded.windows(3)
+                .map(|window| window.iter().collect::<String>())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Tell Cyrillic languages apart by their distinctive letters
+fn cyrillic_language(text: &str) -> (&'static str, f64) {
+    let has = |letters: &[char]| text.to_lowercase().contains(letters);
+    
+    if has(&['ӣ', 'ӯ', 'ҳ', 'ҷ']) {
+        ("tg", 0.95)
+    } else if has(&['җ']) {
+        ("tt", 0.95)
+    } else if has(&['ә', 'ғ', 'қ', 'ұ']) {
+        ("kk", 0.95)
+    } else if has(&['ң', 'ө', 'ү']) {
+        ("ky", 0.8)
+    } else if has(&['ў']) {
+        ("be", 0.95)
+    } else if has(&['ї', 'є', 'ґ', 'і']) {
+        ("uk", 0.95)
+    } else if has(&['ѓ', 'ќ', 'ѕ']) {
+        ("mk", 0.95)
+    } else if has(&['ђ', 'ћ', 'џ', 'ј', 'љ', 'њ']) {
+        ("sr", 0.9)
+    } else if has(&['ы', 'э', 'ё']) {
+        ("ru", 0.95)
+    } else if has(&['ъ']) {
+        ("bg", 0.8)
+    } else {
+        ("ru", 0.6)
+    }
+}
+
+/// Tell Arabic-script languages apart by their extra letters
+fn arabic_language(text: &str) -> (&'static str, f64) {
+    let has = |letters: &[char]| text.contains(letters);
+    
+    if has(&['ڄ', 'ڃ', 'ٻ', 'ڀ', 'ٺ', 'ٽ', 'ڦ', 'ڳ', 'ڱ']) {
+        ("sd", 0.9)
+    } else if has(&['ټ', 'ډ', 'ړ', 'ږ', 'ښ', 'ګ', 'ڼ']) {
+        ("ps", 0.9)
+    } else if has(&['ۇ', 'ۆ', 'ۈ', 'ۋ', 'ې']) {
+        ("ug", 0.9)
+    } else if has(&['ٹ', 'ڈ', 'ڑ', 'ں', 'ے']) {
+        ("ur", 0.9)
+    } else if has(&['پ', 'چ', 'ژ', 'گ', 'ی', 'ک']) {
+        ("fa", 0.85)
+    } else {
+        ("ar", 0.9)
+    }
+}
+
+/// Tell Devanagari languages apart by common words and letters
+fn devanagari_language(text: &str) -> (&'static str, f64) {
+    if text.contains('ळ') || text.contains("आहे") {
+        ("mr", 0.85)
+    } else if text.contains("छ ") || text.contains("छ।") || text.contains("छन्") {
+        ("ne", 0.8)
+    } else {
+        ("hi", 0.8)
+    }
+}
+
+/// Tell Simplified from Traditional Chinese by characters that differ
+fn chinese_variant(text: &str) -> (&'static str, f64) {
+    const SIMPLIFIED: &str = "们个这来时会说对后学国经发过还开关长门问间东车见点爱电号为没";
+    const TRADITIONAL: &str = "們個這來時會說對後學國經發過還開關長門問間東車見點愛電號為沒";
+    
+    let simplified = text.chars().filter(|c| SIMPLIFIED.contains(*c)).count();
+    let traditional = text.chars().filter(|c| TRADITIONAL.contains(*c)).count();
+    
+    if traditional > simplified {
+        ("zh-TW", 0.9)
+    } else {
+        ("zh-CN", 0.9)
+    }
+}
+
+/// Script of a letter, if it is one the detector knows
+fn script_of(c: char) -> Option<Script> {
+    let script = match c as u32 {
+        0x41..=0x5A | 0x61..=0x7A | 0xC0..=0x24F | 0x1E00..=0x1EFF => Script::Latin,
+        0x370..=0x3FF | 0x1F00..=0x1FFF => Script::Greek,
+        0x400..=0x52F => Script::Cyrillic,
+        0x530..=0x58F => Script::Armenian,
+        0x590..=0x5FF => Script::Hebrew,
+        0x600..=0x6FF | 0x750..=0x77F | 0xFB50..=0xFDFF | 0xFE70..=0xFEFF => Script::Arabic,
+        0x900..=0x97F => Script::Devanagari,
+        0x980..=0x9FF => Script::Bengali,
+        0xA00..=0xA7F => Script::Gurmukhi,
+        0xA80..=0xAFF => Script::Gujarati,
+        0xB00..=0xB7F => Script::Oriya,
+        0xB80..=0xBFF => Script::Tamil,
+        0xC00..=0xC7F => Script::Telugu,
+        0xC80..=0xCFF => Script::Kannada,
+        0xD00..=0xD7F => Script::Malayalam,
+        0xD80..=0xDFF => Script::Sinhala,
+        0xE00..=0xE7F => Script::Thai,
+        0xE80..=0xEFF => Script::Lao,
+        0x1000..=0x109F => Script::Myanmar,
+        0x10A0..=0x10FF => Script::Georgian,
+        0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF => Script::Hangul,
+        0x1200..=0x139F => Script::Ethiopic,
+        0x1780..=0x17FF => Script::Khmer,
+        0x3040..=0x30FF => Script::Kana,
+        0x3400..=0x4DBF | 0x4E00..=0x9FFF => Script::Han,
+        _ => return None,
+    };
+    
+    Some(script)
+}
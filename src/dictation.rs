@@ -0,0 +1,172 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use crate::languages;
+use crate::temp_file::TempFile;
+
+/// Extensions of audio files accepted by drag and drop
+const AUDIO_EXTENSIONS: &[&str] = &["wav", "mp3", "ogg", "oga", "opus", "flac", "m4a", "aac", "webm"];
+
+/// A transcript produced by whisper.cpp
+#[derive(Debug, Clone)]
+pub struct Transcript {
+    pub text: String,
+    pub language: Option<String>,
+}
+
+/// whisper.cpp options taken from the settings
+#[derive(Debug, Clone)]
+pub struct WhisperOptions {
+    pub binary: String,
+    pub model: Option<String>,
+}
+
+/// A microphone recording in progress.
+///
+/// Audio is captured with `arecord` as 16 kHz mono WAV, the format whisper.cpp expects.
+pub struct Recording {
+    child: Child,
+    file: TempFile,
+}
+
+impl Recording {
+    /// Start recording from the default microphone
+    pub fn start() -> Result<Self, String> {
+        let file = TempFile::new("dictation", "wav")?;
+        
+        let child = Command::new("arecord")
+            .args(["-q", "-f", "S16_LE", "-r", "16000", "-c", "1", "-t", "wav"])
+            .arg(file.path())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Could not start recording (is arecord installed?): {}", e))?;
+        
+        Ok(Recording { child, file })
+    }
+    
+    /// Stop recording and return the recorded audio, deleted once dropped
+    pub fn stop(mut self) -> Result<TempFile, String> {
+        // SIGINT lets arecord finish the WAV header, unlike Child::kill
+        let interrupted = Command::new("kill")
+            .args(["-INT", &self.child.id().to_string()])
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false);
+        
+        if !interrupted {
+            let _ = self.child.kill();
+        }
+        
+        self.child.wait()
+            .map_err(|e| format!("Recording failed: {}", e))?;
+        
+        Ok(self.file)
+    }
+}
+
+/// Transcribe an audio file with whisper.cpp, auto-detecting the language.
+///
+/// Files that are not already 16 kHz WAV are converted with ffmpeg first.
+pub async fn transcribe(audio_path: &Path, options: &WhisperOptions) -> Result<Transcript, String> {
+    let model = options.model.as_deref()
+        .filter(|m| !m.is_empty())
+        .ok_or_else(|| "No whisper.cpp model configured".to_string())?;
+    
+    // Our own recordings are already 16 kHz mono WAV
+    let converted;
+    let wav_path = if is_whisper_wav(audio_path) {
+        audio_path
+    } else {
+        converted = convert_to_wav(audio_path).await?;
+        converted.path()
+    };
+    
+    let output = tokio::process::Command::new(&options.binary)
+        .args(["-m", model, "-l", "auto", "-nt", "-f"])
+        .arg(wav_path)
+        .output()
+        .await
+        .map_err(|e| format!("Could not start {}: {}", options.binary, e))?;
+    
+    if !output.status.success() {
+        return Err(format!(
+            "whisper.cpp failed: {}",
+            String::from_utf8_lossy(&output.stderr).lines().last().unwrap_or_default()
+        ));
+    }
+    
+    // The transcript goes to stdout, the detected language is logged on stderr
+    let text = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    
+    if text.is_empty() {
+        return Err("No speech recognized".to_string());
+    }
+    
+    Ok(Transcript {
+        text,
+        language: detected_language(&String::from_utf8_lossy(&output.stderr)),
+    })
+}
+
+/// Check whether a file looks like audio, judging by its extension
+pub fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .map_or(false, |ext| AUDIO_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// Check whether a file is 16-bit PCM WAV at 16 kHz mono, the format
+/// whisper.cpp reads without conversion, from its canonical header
+fn is_whisper_wav(path: &Path) -> bool {
+    let mut header = [0u8; 36];
+    if File::open(path).and_then(|mut file| file.read_exact(&mut header)).is_err() {
+        return false;
+    }
+    
+    let u16_at = |offset: usize| u16::from_le_bytes([header[offset], header[offset + 1]]);
+    let u32_at = |offset: usize| u32::from_le_bytes([header[offset], header[offset + 1], header[offset + 2], header[offset + 3]]);
+    
+    &header[0..4] == b"RIFF"
+        && &header[8..12] == b"WAVE"
+        && &header[12..16] == b"fmt "
+        && u16_at(20) == 1      // PCM
+        && u16_at(22) == 1      // Mono
+        && u32_at(24) == 16000  // Sample rate
+        && u16_at(34) == 16     // Bits per sample
+}
+
+/// Convert any audio file to 16 kHz mono WAV with ffmpeg
+async fn convert_to_wav(path: &Path) -> Result<TempFile, String> {
+    let wav_file = TempFile::new("dictation-input", "wav")?;
+    
+    let status = tokio::process::Command::new("ffmpeg")
+        .args(["-y", "-loglevel", "error", "-i"])
+        .arg(path)
+        .args(["-ar", "16000", "-ac", "1", "-c:a", "pcm_s16le"])
+        .arg(wav_file.path())
+        .status()
+        .await
+        .map_err(|e| format!("Could not start ffmpeg to convert the audio: {}", e))?;
+    
+    if !status.success() {
+        return Err(format!("Could not convert {} to WAV", path.display()));
+    }
+    
+    Ok(wav_file)
+}
+
+/// Parse the "auto-detected language: xx (p = 0.97)" line whisper.cpp logs
+fn detected_language(log: &str) -> Option<String> {
+    log.lines()
+        .find_map(|line| line.split("auto-detected language:").nth(1))
+        .and_then(|rest| rest.split_whitespace().next())
+        .map(languages::normalize)
+}
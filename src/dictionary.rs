@@ -0,0 +1,306 @@
+use serde_json::Value;
+
+/// Longest input (in characters) still treated as a dictionary lookup
+const MAX_LOOKUP_CHARS: usize = 40;
+
+/// Most words a short phrase may contain to be treated as a dictionary lookup
+const MAX_LOOKUP_WORDS: usize = 3;
+
+/// Dictionary data returned alongside the translation of a single word or short phrase
+#[derive(Debug, Clone, Default)]
+pub struct DictionaryResult {
+    pub alternatives: Vec<String>,
+    pub entries: Vec<DictionaryEntry>,
+    pub definitions: Vec<Definition>,
+    pub synonyms: Vec<SynonymGroup>,
+    pub examples: Vec<String>,
+}
+
+/// Translations of the word grouped under one part of speech
+#[derive(Debug, Clone)]
+pub struct DictionaryEntry {
+    pub part_of_speech: String,
+    pub translations: Vec<DictionaryTranslation>,
+}
+
+/// A single dictionary translation with the source words it translates back to
+#[derive(Debug, Clone)]
+pub struct DictionaryTranslation {
+    pub text: String,
+    pub back_translations: Vec<String>,
+    pub confidence: Option<f64>,
+}
+
+/// A definition of the source word in the source language
+#[derive(Debug, Clone)]
+pub struct Definition {
+    pub part_of_speech: String,
+    pub text: String,
+    pub example: Option<String>,
+}
+
+/// Synonyms of the source word for one part of speech
+#[derive(Debug, Clone)]
+pub struct SynonymGroup {
+    pub part_of_speech: String,
+    pub synonyms: Vec<String>,
+}
+
+/// Check whether the input is short enough to ask for dictionary data
+pub fn is_dictionary_query(text: &str) -> bool {
+    let text = text.trim();
+    
+    !text.is_empty()
+        && !text.contains('\n')
+        && text.chars().count() <= MAX_LOOKUP_CHARS
+        && text.split_whitespace().count() <= MAX_LOOKUP_WORDS
+}
+
+impl DictionaryResult {
+    /// Check whether any dictionary data was found
+    pub fn is_empty(&self) -> bool {
+        self.alternatives.is_empty()
+            && self.entries.is_empty()
+            && self.definitions.is_empty()
+            && self.synonyms.is_empty()
+            && self.examples.is_empty()
+    }
+    
+    /// Parse the dictionary parts (`dt=at`, `bd`, `md`, `ss`, `ex`) of a Google Translate response
+    pub fn from_google_response(json: &Value, translation: &str) -> Option<Self> {
+        let mut result = DictionaryResult::default();
+        
+        // Dictionary entries: [pos, [terms], [[term, [back translations], null, score]], base form, ...]
+        for entry in json[1].as_array().into_iter().flatten() {
+            let translations: Vec<DictionaryTranslation> = entry[2].as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|term| {
+                    Some(DictionaryTranslation {
+                        text: term[0].as_str()?.to_string(),
+                        back_translations: string_array(&term[1]),
+                        confidence: term[3].as_f64(),
+                    })
+                })
+                .collect();
+            
+            if !translations.is_empty() {
+                result.entries.push(DictionaryEntry {
+                    part_of_speech: entry[0].as_str().unwrap_or_default().to_string(),
+                    translations,
+                });
+            }
+        }
+        
+        // Alternative translations: [[segment, null, [[alternative, score, ...]], ...]]
+        for segment in json[5].as_array().into_iter().flatten() {
+            for alternative in segment[2].as_array().into_iter().flatten() {
+                if let Some(text) = alternative[0].as_str() {
+                    if text != translation && !result.alternatives.iter().any(|a| a == text) {
+                        result.alternatives.push(text.to_string());
+                    }
+                }
+            }
+        }
+        
+        // Synonyms: [pos, [[[synonyms], id]], base form]
+        for group in json[11].as_array().into_iter().flatten() {
+            let synonyms: Vec<String> = group[1].as_array()
+                .into_iter()
+                .flatten()
+                .flat_map(|set| string_array(&set[0]))
+                .collect();
+            
+            if !synonyms.is_empty() {
+                result.synonyms.push(SynonymGroup {
+                    part_of_speech: group[0].as_str().unwrap_or_default().to_string(),
+                    synonyms,
+                });
+            }
+        }
+        
+        // Definitions: [pos, [[gloss, id, example]], base form]
+        for group in json[12].as_array().into_iter().flatten() {
+            let part_of_speech = group[0].as_str().unwrap_or_default();
+            for definition in group[1].as_array().into_iter().flatten() {
+                if let Some(text) = definition[0].as_str() {
+                    result.definitions.push(Definition {
+                        part_of_speech: part_of_speech.to_string(),
+                        text: text.to_string(),
+                        example: definition[2].as_str().map(String::from),
+                    });
+                }
+            }
+        }
+        
+        // Examples: [[[example with <b> markers], ...]]
+        for example in json[13][0].as_array().into_iter().flatten() {
+            if let Some(text) = example[0].as_str() {
+                result.examples.push(text.to_string());
+            }
+        }
+        
+        if result.is_empty() {
+            None
+        } else {
+            Some(result)
+        }
+    }
+    
+    /// Parse an Azure Translator `/dictionary/lookup` response
+    pub fn from_azure_lookup(json: &Value) -> Option<Self> {
+        let mut result = DictionaryResult::default();
+        
+        // Translations come as a flat list, each tagged with its part of speech
+        for translation in json[0]["translations"].as_array().into_iter().flatten() {
+            let text = match translation["displayTarget"].as_str() {
+                Some(text) => text.to_string(),
+                None => continue,
+            };
+            let part_of_speech = azure_part_of_speech(translation["posTag"].as_str().unwrap_or_default());
+            let dictionary_translation = DictionaryTranslation {
+                text,
+                back_translations: translation["backTranslations"].as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|back| back["displayText"].as_str().map(String::from))
+                    .collect(),
+                confidence: translation["confidence"].as_f64(),
+            };
+            
+            match result.entries.iter_mut().find(|entry| entry.part_of_speech == part_of_speech) {
+                Some(entry) => entry.translations.push(dictionary_translation),
+                None => result.entries.push(DictionaryEntry {
+                    part_of_speech: part_of_speech.to_string(),
+                    translations: vec![dictionary_translation],
+                }),
+            }
+        }
+        
+        if result.is_empty() {
+            None
+        } else {
+            Some(result)
+        }
+    }
+    
+    /// Add the sentences of an Azure Translator `/dictionary/examples` response
+    pub fn add_azure_examples(&mut self, json: &Value) {
+        for pair in json.as_array().into_iter().flatten() {
+            for example in pair["examples"].as_array().into_iter().flatten() {
+                let part = |key: &str| example[key].as_str().unwrap_or_default();
+                
+                // Mark the looked-up term the same way Google examples do
+                self.examples.push(format!(
+                    "{}<b>{}</b>{} — {}<b>{}</b>{}",
+                    part("sourcePrefix"), part("sourceTerm"), part("sourceSuffix"),
+                    part("targetPrefix"), part("targetTerm"), part("targetSuffix"),
+                ));
+            }
+        }
+    }
+    
+    /// Format the dictionary data as Pango markup for display
+    pub fn to_markup(&self) -> String {
+        let mut sections = Vec::new();
+        
+        if !self.alternatives.is_empty() {
+            let alternatives: Vec<String> = self.alternatives.iter().map(|a| escape_markup(a)).collect();
+            sections.push(format!("<b>Alternatives</b>\n{}", alternatives.join(", ")));
+        }
+        
+        for entry in &self.entries {
+            let mut lines = vec![format!("<b>{}</b>", escape_markup(&entry.part_of_speech))];
+            for translation in &entry.translations {
+                let mut line = format!("• {}", escape_markup(&translation.text));
+                if let Some(confidence) = translation.confidence {
+                    line.push_str(&format!(" <small>({:.0}%)</small>", confidence * 100.0));
+                }
+                if !translation.back_translations.is_empty() {
+                    line.push_str(&format!(
+                        " — <i>{}</i>",
+                        escape_markup(&translation.back_translations.join(", "))
+                    ));
+                }
+                lines.push(line);
+            }
+            sections.push(lines.join("\n"));
+        }
+        
+        if !self.definitions.is_empty() {
+            let mut lines = vec!["<b>Definitions</b>".to_string()];
+            for definition in &self.definitions {
+                let mut line = if definition.part_of_speech.is_empty() {
+                    format!("• {}", escape_markup(&definition.text))
+                } else {
+                    format!("• <i>{}</i> {}", escape_markup(&definition.part_of_speech), escape_markup(&definition.text))
+                };
+                if let Some(example) = &definition.example {
+                    line.push_str(&format!("\n    “{}”", escape_markup(example)));
+                }
+                lines.push(line);
+            }
+            sections.push(lines.join("\n"));
+        }
+        
+        if !self.synonyms.is_empty() {
+            let mut lines = vec!["<b>Synonyms</b>".to_string()];
+            for group in &self.synonyms {
+                lines.push(format!(
+                    "• <i>{}</i> {}",
+                    escape_markup(&group.part_of_speech),
+                    escape_markup(&group.synonyms.join(", "))
+                ));
+            }
+            sections.push(lines.join("\n"));
+        }
+        
+        if !self.examples.is_empty() {
+            let mut lines = vec!["<b>Examples</b>".to_string()];
+            for example in &self.examples {
+                // Examples come with <b> around the looked-up word, which Pango understands
+                let example = escape_markup(example)
+                    .replace("&lt;b&gt;", "<b>")
+                    .replace("&lt;/b&gt;", "</b>");
+                lines.push(format!("• {}", example));
+            }
+            sections.push(lines.join("\n"));
+        }
+        
+        sections.join("\n\n")
+    }
+}
+
+/// Readable name of an Azure part-of-speech tag
+fn azure_part_of_speech(tag: &str) -> &str {
+    match tag {
+        "ADJ" => "adjective",
+        "ADV" => "adverb",
+        "CONJ" => "conjunction",
+        "DET" => "determiner",
+        "MODAL" => "modal verb",
+        "NOUN" => "noun",
+        "PREP" => "preposition",
+        "PRON" => "pronoun",
+        "VERB" => "verb",
+        _ => "other",
+    }
+}
+
+/// Collect the strings of a JSON array, skipping anything else
+fn string_array(value: &Value) -> Vec<String> {
+    value.as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str().map(String::from))
+        .collect()
+}
+
+/// Escape text for use inside Pango markup
+pub fn escape_markup(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
@@ -0,0 +1,128 @@
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use once_cell::sync::Lazy;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use crate::translation::TranslationError;
+
+/// OAuth scope for the Cloud Translation API
+const TRANSLATION_SCOPE: &str = "https://www.googleapis.com/auth/cloud-translation";
+
+/// Lifetime requested for the signed assertion (Google's maximum)
+const ASSERTION_LIFETIME_SECS: u64 = 3600;
+
+/// Refresh tokens this long before they expire
+const EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+
+/// Access tokens by service-account file path
+static TOKEN_CACHE: Lazy<Mutex<HashMap<String, CachedToken>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+struct CachedToken {
+    access_token: String,
+    expires_at: Instant,
+}
+
+/// The fields of a service-account JSON key file we need
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServiceAccountKey {
+    pub client_email: String,
+    pub private_key: String,
+    pub token_uri: String,
+    pub project_id: Option<String>,
+}
+
+/// Claims of the JWT exchanged for an access token
+#[derive(Serialize)]
+struct Claims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: u64,
+    exp: u64,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+impl ServiceAccountKey {
+    /// Read a service-account key file downloaded from the Cloud console
+    pub fn load(path: &str) -> Result<Self, TranslationError> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| TranslationError::InvalidConfig(format!("Could not read service account file '{}': {}", path, e)))?;
+        
+        serde_json::from_str(&contents)
+            .map_err(|e| TranslationError::InvalidConfig(format!("Invalid service account file '{}': {}", path, e)))
+    }
+}
+
+/// Get an OAuth access token for the service account, reusing a cached one
+/// until shortly before it expires
+pub async fn access_token(client: &Client, credentials_path: &str) -> Result<String, TranslationError> {
+    if let Ok(cache) = TOKEN_CACHE.lock() {
+        if let Some(token) = cache.get(credentials_path) {
+            if token.expires_at > Instant::now() + EXPIRY_MARGIN {
+                return Ok(token.access_token.clone());
+            }
+        }
+    }
+    
+    let key = ServiceAccountKey::load(credentials_path)?;
+    let assertion = signed_assertion(&key)?;
+    
+    let params = [
+        ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+        ("assertion", assertion.as_str()),
+    ];
+    let response = client.post(&key.token_uri)
+        .form(&params)
+        .send()
+        .await?;
+    
+    if !response.status().is_success() {
+        return Err(match response.status().as_u16() {
+            // A revoked or deleted key is reported as a bad request
+            400 | 401 | 403 => TranslationError::Unauthorized,
+            _ => TranslationError::Status(response.status()),
+        });
+    }
+    
+    let token: TokenResponse = response.json().await
+        .map_err(|e| TranslationError::InvalidResponse(e.to_string()))?;
+    
+    if let Ok(mut cache) = TOKEN_CACHE.lock() {
+        cache.insert(credentials_path.to_string(), CachedToken {
+            access_token: token.access_token.clone(),
+            expires_at: Instant::now() + Duration::from_secs(token.expires_in),
+        });
+    }
+    
+    Ok(token.access_token)
+}
+
+/// Sign the JWT assertion with the service account's private key
+fn signed_assertion(key: &ServiceAccountKey) -> Result<String, TranslationError> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    
+    let claims = Claims {
+        iss: &key.client_email,
+        scope: TRANSLATION_SCOPE,
+        aud: &key.token_uri,
+        iat: now,
+        exp: now + ASSERTION_LIFETIME_SECS,
+    };
+    
+    let encoding_key = EncodingKey::from_rsa_pem(key.private_key.as_bytes())
+        .map_err(|e| TranslationError::InvalidConfig(format!("Invalid service account private key: {}", e)))?;
+    
+    encode(&Header::new(Algorithm::RS256), &claims, &encoding_key)
+        .map_err(|e| TranslationError::InvalidConfig(format!("Could not sign service account token: {}", e)))
+}
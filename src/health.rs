@@ -0,0 +1,111 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use gettextrs::gettext;
+use crate::i18n::gettext_f;
+use crate::translation::TranslationService;
+
+/// Number of recent requests kept per service
+const WINDOW_SIZE: usize = 50;
+
+/// Outcome of one request
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    success: bool,
+    latency: Duration,
+}
+
+/// Health of a service over its recent requests
+#[derive(Debug, Clone)]
+pub struct ServiceHealth {
+    pub requests: usize,
+    pub success_rate: f64,
+    pub p50: Option<Duration>,
+    pub p95: Option<Duration>,
+}
+
+impl ServiceHealth {
+    /// Short summary for the settings dialog
+    pub fn summary(&self) -> String {
+        if self.requests == 0 {
+            return gettext("No requests yet");
+        }
+        
+        let mut summary = gettext_f(
+            // xgettext:no-c-format
+            "{}% successful over {} requests",
+            &[&format!("{:.0}", self.success_rate * 100.0), &self.requests.to_string()]
+        );
+        if let (Some(p50), Some(p95)) = (self.p50, self.p95) {
+            summary.push_str(&gettext_f(", p50 {} ms, p95 {} ms", &[&p50.as_millis().to_string(), &p95.as_millis().to_string()]));
+        }
+        
+        summary
+    }
+}
+
+/// Rolling success rate and latency per service, shared by all clones of the
+/// translation manager so results from every request are counted
+#[derive(Debug, Clone, Default)]
+pub struct HealthTracker {
+    samples: Arc<Mutex<HashMap<TranslationService, VecDeque<Sample>>>>,
+}
+
+impl HealthTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Record the outcome of a request
+    pub fn record(&self, service: &TranslationService, success: bool, latency: Duration) {
+        if let Ok(mut samples) = self.samples.lock() {
+            let window = samples.entry(service.clone()).or_default();
+            if window.len() == WINDOW_SIZE {
+                window.pop_front();
+            }
+            window.push_back(Sample { success, latency });
+        }
+    }
+    
+    /// Current health of a service
+    pub fn health(&self, service: &TranslationService) -> ServiceHealth {
+        let samples = self.samples.lock().ok();
+        let window = samples.as_ref().and_then(|samples| samples.get(service));
+        
+        let requests = window.map_or(0, |window| window.len());
+        let successes = window.map_or(0, |window| window.iter().filter(|sample| sample.success).count());
+        
+        // Latency percentiles only cover successful requests
+        let mut latencies: Vec<Duration> = window
+            .map(|window| window.iter().filter(|sample| sample.success).map(|sample| sample.latency).collect())
+            .unwrap_or_default();
+        latencies.sort();
+        
+        let percentile = |p: usize| latencies.get((latencies.len().saturating_sub(1)) * p / 100).copied();
+        
+        ServiceHealth {
+            requests,
+            success_rate: if requests == 0 { 1.0 } else { successes as f64 / requests as f64 },
+            p50: percentile(50),
+            p95: percentile(95),
+        }
+    }
+    
+    /// Sort services from healthiest to least healthy: highest success rate
+    /// first, then lowest median latency. Services without data keep their
+    /// relative order after measured services with the same success rate.
+    pub fn sort_by_health(&self, services: &mut [TranslationService]) {
+        services.sort_by(|a, b| {
+            let a = self.health(a);
+            let b = self.health(b);
+            
+            b.success_rate.total_cmp(&a.success_rate)
+                .then_with(|| match (a.p50, b.p50) {
+                    (Some(a), Some(b)) => a.cmp(&b),
+                    (Some(_), None) => std::cmp::Ordering::Less,
+                    (None, Some(_)) => std::cmp::Ordering::Greater,
+                    (None, None) => std::cmp::Ordering::Equal,
+                })
+        });
+    }
+}
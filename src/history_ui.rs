@@ -5,12 +5,31 @@ use gtk::{
     MessageType, ButtonsType
 };
 use std::rc::Rc;
-use std::cell::RefCell;
-use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
 
-use crate::database::{Database, Translation, TranslationList};
+use crate::database::{Database, HistoryFilter, HistorySort, SearchMatch, Translation, TranslationList, TranslationOrigin};
+use crate::detection;
+use crate::languages;
+use crate::translation::TranslationService;
+use crate::ui_helpers::{match_markup, text_direction};
+use crate::i18n::gettext_f;
+use gettextrs::gettext;
 
 const LIST_OPTION_CREATE_NEW: &str = "CREATE_NEW_LIST";
+const SEARCH_RESULT_LIMIT: i64 = 200;
+const FILTER_ALL: &str = "all";
+const SORT_RECENT: &str = "recent";
+const SORT_FREQUENT: &str = "frequent";
+
+/// Name of a history entry's origin as shown in the filter and rows
+fn origin_label(origin: TranslationOrigin) -> String {
+    match origin {
+        TranslationOrigin::Hotkey => gettext("Hotkey"),
+        TranslationOrigin::Typed => gettext("Typed"),
+        TranslationOrigin::Clipboard => gettext("Clipboard"),
+        TranslationOrigin::Speech => gettext("Speech"),
+    }
+}
 
 /// Helper function to create and show a message dialog
 fn show_message_dialog(
@@ -39,6 +58,9 @@ pub struct HistoryPanel {
     main_box: GtkBox,
     translation_list: ListBox,
     list_selector: ComboBoxText,
+    search_entry: SearchEntry,
+    filter: Rc<RefCell<HistoryFilter>>,
+    view_limit: Rc<Cell<i64>>,
     lists: Rc<RefCell<Vec<TranslationList>>>,
     db: Rc<RefCell<Database>>,
     active_list_id: Rc<RefCell<Option<i64>>>,
@@ -46,7 +68,6 @@ pub struct HistoryPanel {
     output_buffer: Rc<RefCell<TextBuffer>>,
     source_lang: Rc<RefCell<ComboBoxText>>,
     target_lang: Rc<RefCell<ComboBoxText>>,
-    translation_id_map: Rc<RefCell<HashMap<u32, i64>>>,
 }
 
 impl HistoryPanel {
@@ -56,6 +77,7 @@ impl HistoryPanel {
         output_buffer: Rc<RefCell<TextBuffer>>,
         source_lang: Rc<RefCell<ComboBoxText>>,
         target_lang: Rc<RefCell<ComboBoxText>>,
+        view_limit: i64,
     ) -> Self {
         // Main vertical box
         let main_box = GtkBox::new(Orientation::Vertical, 10);
@@ -69,13 +91,14 @@ impl HistoryPanel {
         let header_box = GtkBox::new(Orientation::Horizontal, 10);
         header_box.set_margin_bottom(10);
         
-        let title = Label::new(Some("Translation History"));
+        let title = Label::new(Some(&gettext("Translation History")));
         title.set_hexpand(true);
         title.set_halign(gtk::Align::Start);
         title.add_css_class("title-3");
         
         let search_entry = SearchEntry::new();
-        search_entry.set_placeholder_text(Some("Search translations..."));
+        search_entry.set_placeholder_text(Some(&gettext("Search translations...")));
+        search_entry.set_tooltip_text(Some(&gettext("Matches word forms. Use word* for prefixes, \"quotes\" for phrases and AND, OR, NOT to combine terms.")));
         
         header_box.append(&title);
         header_box.append(&search_entry);
@@ -86,16 +109,16 @@ impl HistoryPanel {
         let list_box = GtkBox::new(Orientation::Horizontal, 10);
         list_box.set_margin_bottom(10);
         
-        let list_label = Label::new(Some("Save to List:"));
+        let list_label = Label::new(Some(&gettext("Save to List:")));
         
         let list_selector = ComboBoxText::new();
         list_selector.set_margin_end(10);
         list_selector.set_hexpand(true);
         
-        let refresh_button = Button::with_label("Refresh");
-        let delete_list_button = Button::with_label("Delete List");
+        let refresh_button = Button::with_label(&gettext("Refresh"));
+        let delete_list_button = Button::with_label(&gettext("Delete List"));
         delete_list_button.add_css_class("destructive-action");
-        let export_button = Button::with_label("Export");
+        let export_button = Button::with_label(&gettext("Export"));
         
         list_box.append(&list_label);
         list_box.append(&list_selector);
@@ -105,8 +128,42 @@ impl HistoryPanel {
         
         main_box.append(&list_box);
         
+        // Service and origin filters
+        let filter_box = GtkBox::new(Orientation::Horizontal, 10);
+        filter_box.set_margin_bottom(10);
+        
+        let filter_label = Label::new(Some(&gettext("Show:")));
+        
+        let service_filter = ComboBoxText::new();
+        service_filter.append(Some(FILTER_ALL), &gettext("All services"));
+        for service in TranslationService::all_services() {
+            service_filter.append(Some(service.config_name()), &service.to_string());
+        }
+        service_filter.set_active_id(Some(FILTER_ALL));
+        
+        let origin_filter = ComboBoxText::new();
+        origin_filter.append(Some(FILTER_ALL), &gettext("All sources"));
+        for origin in TranslationOrigin::ALL {
+            origin_filter.append(Some(origin.name()), &origin_label(origin));
+        }
+        origin_filter.set_active_id(Some(FILTER_ALL));
+        
+        let sort_selector = ComboBoxText::new();
+        sort_selector.append(Some(SORT_RECENT), &gettext("Most recent"));
+        sort_selector.append(Some(SORT_FREQUENT), &gettext("Most used"));
+        sort_selector.set_active_id(Some(SORT_RECENT));
+        sort_selector.set_halign(gtk::Align::End);
+        sort_selector.set_hexpand(true);
+        
+        filter_box.append(&filter_label);
+        filter_box.append(&service_filter);
+        filter_box.append(&origin_filter);
+        filter_box.append(&sort_selector);
+        
+        main_box.append(&filter_box);
+        
         // Translations list
-        let list_frame = Frame::new(Some("Recent Translations"));
+        let list_frame = Frame::new(Some(&gettext("Recent Translations")));
         
         let scroll = ScrolledWindow::new();
         scroll.set_vexpand(true);
@@ -127,12 +184,15 @@ impl HistoryPanel {
         action_box.set_halign(gtk::Align::End);
         action_box.set_margin_top(10);
         
-        let reuse_button = Button::with_label("Reuse Selected");
-        let add_to_list_button = Button::with_label("Add to List");
-        let delete_button = Button::with_label("Delete");
+        let reuse_button = Button::with_label(&gettext("Reuse Selected"));
+        let pin_button = Button::with_label(&gettext("Pin"));
+        pin_button.set_tooltip_text(Some(&gettext("Pinned translations are never removed from the history automatically")));
+        let add_to_list_button = Button::with_label(&gettext("Add to List"));
+        let delete_button = Button::with_label(&gettext("Delete"));
         delete_button.add_css_class("destructive-action");
         
         action_box.append(&reuse_button);
+        action_box.append(&pin_button);
         action_box.append(&add_to_list_button);
         action_box.append(&delete_button);
         
@@ -145,6 +205,9 @@ impl HistoryPanel {
             main_box,
             translation_list,
             list_selector,
+            search_entry: search_entry.clone(),
+            filter: Rc::new(RefCell::new(HistoryFilter::default())),
+            view_limit: Rc::new(Cell::new(view_limit)),
             lists: lists.clone(),
             db: db.clone(),
             active_list_id,
@@ -152,7 +215,6 @@ impl HistoryPanel {
             output_buffer,
             source_lang,
             target_lang,
-            translation_id_map: Rc::new(RefCell::new(HashMap::new())),
         };
         
         // Load lists and refresh history
@@ -166,6 +228,31 @@ impl HistoryPanel {
             history_panel_ref.search_translations(&query);
         });
         
+        // Connect filters
+        let history_panel_ref = history_panel.clone();
+        service_filter.connect_changed(move |combo| {
+            history_panel_ref.filter.borrow_mut().service = combo.active_id()
+                .filter(|id| id.as_str() != FILTER_ALL)
+                .map(|id| id.to_string());
+            history_panel_ref.reload();
+        });
+        
+        let history_panel_ref = history_panel.clone();
+        origin_filter.connect_changed(move |combo| {
+            history_panel_ref.filter.borrow_mut().origin = combo.active_id()
+                .and_then(|id| TranslationOrigin::from_name(&id));
+            history_panel_ref.reload();
+        });
+        
+        let history_panel_ref = history_panel.clone();
+        sort_selector.connect_changed(move |combo| {
+            history_panel_ref.filter.borrow_mut().sort = match combo.active_id().as_deref() {
+                Some(SORT_FREQUENT) => HistorySort::Frequent,
+                _ => HistorySort::Recent,
+            };
+            history_panel_ref.reload();
+        });
+        
         // Connect refresh button
         let history_panel_ref = history_panel.clone();
         refresh_button.connect_clicked(move |_| {
@@ -201,6 +288,21 @@ impl HistoryPanel {
             history_panel_ref.reuse_selected_translation();
         });
         
+        // Connect pin button, labelled for the selected row
+        let history_panel_ref = history_panel.clone();
+        pin_button.connect_clicked(move |_| {
+            history_panel_ref.toggle_selected_pin();
+        });
+        
+        let history_panel_ref = history_panel.clone();
+        let pin_button_ref = pin_button.clone();
+        history_panel.translation_list.connect_row_selected(move |_, _| {
+            let pinned = history_panel_ref.selected_translation()
+                .and_then(|(_, translation_id)| history_panel_ref.db.borrow().get_translation_by_id(translation_id).ok().flatten())
+                .map_or(false, |translation| translation.pinned);
+            pin_button_ref.set_label(&if pinned { gettext("Unpin") } else { gettext("Pin") });
+        });
+        
         // Connect add to list button
         let history_panel_ref = history_panel.clone();
         add_to_list_button.connect_clicked(move |_| {
@@ -237,6 +339,9 @@ impl HistoryPanel {
             main_box: self.main_box.clone(),
             translation_list: self.translation_list.clone(),
             list_selector: self.list_selector.clone(),
+            search_entry: self.search_entry.clone(),
+            filter: self.filter.clone(),
+            view_limit: self.view_limit.clone(),
             lists: self.lists.clone(),
             db: self.db.clone(),
             active_list_id: self.active_list_id.clone(),
@@ -244,7 +349,6 @@ impl HistoryPanel {
             output_buffer: self.output_buffer.clone(),
             source_lang: self.source_lang.clone(),
             target_lang: self.target_lang.clone(),
-            translation_id_map: self.translation_id_map.clone(),
         }
     }
     
@@ -253,7 +357,7 @@ impl HistoryPanel {
         self.list_selector.remove_all();
         
         // Add "All Translations" option
-        self.list_selector.append(None, "All Translations");
+        self.list_selector.append(None, &gettext("All Translations"));
         
         // Add all lists from the database
         if let Ok(lists) = self.db.borrow().get_lists() {
@@ -265,7 +369,7 @@ impl HistoryPanel {
         }
         
         // Add "Create New List" option
-        self.list_selector.append(Some(LIST_OPTION_CREATE_NEW), "Create New List...");
+        self.list_selector.append(Some(LIST_OPTION_CREATE_NEW), &gettext("Create New List..."));
         
         // Set active to "All Translations"
         self.list_selector.set_active(Some(0));
@@ -280,7 +384,7 @@ impl HistoryPanel {
         // Check if we have an active list
         if let Some(list_id) = *self.active_list_id.borrow() {
             // Load translations from the active list
-            if let Ok(translations) = self.db.borrow().get_list_translations(list_id) {
+            if let Ok(translations) = self.db.borrow().get_list_translations(list_id, &self.filter.borrow()) {
                 for translation in translations {
                     self.add_translation_to_list(&translation);
                 }
@@ -298,7 +402,7 @@ impl HistoryPanel {
         }
         
         // Load all translations from database
-        if let Ok(translations) = self.db.borrow().get_translations(100) {
+        if let Ok(translations) = self.db.borrow().get_translations(self.view_limit.get(), &self.filter.borrow()) {
             for translation in translations {
                 self.add_translation_to_list(&translation);
             }
@@ -312,13 +416,19 @@ impl HistoryPanel {
         }
         
         // Load translations from database
-        if let Ok(translations) = self.db.borrow().get_list_translations(list_id) {
+        if let Ok(translations) = self.db.borrow().get_list_translations(list_id, &self.filter.borrow()) {
             for translation in translations {
                 self.add_translation_to_list(&translation);
             }
         }
     }
     
+    /// Show the history again, keeping the current search
+    fn reload(&self) {
+        let query = self.search_entry.text().to_string();
+        self.search_translations(&query);
+    }
+    
     fn search_translations(&self, query: &str) {
         // Clear the list
         while let Some(child) = self.translation_list.first_child() {
@@ -335,29 +445,33 @@ impl HistoryPanel {
             return;
         }
         
-        // Search translations in database
-        if let Ok(translations) = self.db.borrow().search_translations(query) {
-            for translation in translations {
-                self.add_translation_to_list(&translation);
+        // Search translations in database, best matches first
+        if let Ok(matches) = self.db.borrow().search_translations(query, SEARCH_RESULT_LIMIT, &self.filter.borrow()) {
+            for search_match in matches {
+                self.add_translation_row(&search_match.translation, Some(&search_match));
             }
         }
     }
     
     fn add_translation_to_list(&self, translation: &Translation) {
+        self.add_translation_row(translation, None);
+    }
+    
+    /// Add a row for a translation, showing the highlighted snippets of a search match
+    fn add_translation_row(&self, translation: &Translation, search_match: Option<&SearchMatch>) {
         // Format timestamp nicely
-        let dt = chrono::DateTime::parse_from_rfc3339(&translation.timestamp);
+        let dt = chrono::DateTime::parse_from_rfc3339(&translation.last_used);
         let formatted_date = match dt {
             Ok(dt) => dt.format("%Y-%m-%d %H:%M").to_string(),
-            Err(_) => translation.timestamp.clone(),
+            Err(_) => translation.last_used.clone(),
         };
         
         // Create a row for the translation
         let row = ListBoxRow::new();
         row.set_selectable(true);
         
-        // Store the translation ID in our HashMap
-        let widget_id = row.widget_name().to_string().parse::<u32>().unwrap_or(0);
-        self.translation_id_map.borrow_mut().insert(widget_id, translation.id);
+        // Keep the translation ID on the row itself
+        row.set_widget_name(&translation.id.to_string());
                 
         // Create a container for the row
         let row_box = GtkBox::new(Orientation::Vertical, 5);
@@ -372,11 +486,17 @@ impl HistoryPanel {
         
         // Source and target languages
         let lang_label = Label::new(Some(&format!("{} → {}", 
-                                               translation.source_lang, 
-                                               translation.target_lang)));
+                                               languages::display_name(&translation.source_lang), 
+                                               languages::display_name(&translation.target_lang))));
         lang_label.add_css_class("caption-heading");
         lang_label.set_halign(gtk::Align::Start);
         
+        // Service, detected language, latency and origin
+        let details_label = Label::new(Some(&Self::translation_details(translation)));
+        details_label.add_css_class("caption");
+        details_label.add_css_class("dim-label");
+        details_label.set_halign(gtk::Align::Start);
+        
         // Timestamp
         let time_label = Label::new(Some(&formatted_date));
         time_label.add_css_class("caption");
@@ -384,16 +504,29 @@ impl HistoryPanel {
         time_label.set_hexpand(true);
         
         header_box.append(&lang_label);
+        header_box.append(&details_label);
         header_box.append(&time_label);
         
-        // Source and target text sections
-        let source_label = Label::new(Some(&translation.source_text));
+        // Source and target text sections, isolated and laid out in the
+        // direction of their language so mixed-direction text stays readable
+        let source_lang = detection::resolve_auto(&translation.source_text, &translation.source_lang);
+        let source_label = Label::new(None);
+        match search_match {
+            Some(search_match) => source_label.set_markup(&match_markup(&languages::isolate(&search_match.source_snippet))),
+            None => source_label.set_text(&languages::isolate(&translation.source_text)),
+        }
+        source_label.set_direction(text_direction(source_lang));
         source_label.set_halign(gtk::Align::Start);
         source_label.set_wrap(true);
         source_label.set_max_width_chars(50);
         source_label.set_ellipsize(gtk::pango::EllipsizeMode::End);
         
-        let target_label = Label::new(Some(&translation.target_text));
+        let target_label = Label::new(None);
+        match search_match {
+            Some(search_match) => target_label.set_markup(&match_markup(&languages::isolate(&search_match.target_snippet))),
+            None => target_label.set_text(&languages::isolate(&translation.target_text)),
+        }
+        target_label.set_direction(text_direction(&translation.target_lang));
         target_label.set_halign(gtk::Align::Start);
         target_label.add_css_class("dim-label");
         target_label.set_wrap(true);
@@ -411,6 +544,34 @@ impl HistoryPanel {
         self.translation_list.append(&row);
     }
     
+    /// Summary of the metadata recorded with a translation
+    fn translation_details(translation: &Translation) -> String {
+        let mut details = Vec::new();
+        
+        if let Some(service) = translation.service.as_deref() {
+            details.push(TranslationService::from_config_name(service)
+                .map_or_else(|| service.to_string(), |service| service.to_string()));
+        }
+        if let Some(detected_lang) = translation.detected_lang.as_deref() {
+            details.push(gettext_f("detected {}", &[&languages::display_name(detected_lang)]));
+        }
+        if let Some(latency_ms) = translation.latency_ms {
+            details.push(gettext_f("{} ms", &[&latency_ms.to_string()]));
+        }
+        details.push(gettext_f("{} characters", &[&translation.char_count.to_string()]));
+        if let Some(origin) = translation.origin {
+            details.push(origin_label(origin));
+        }
+        if translation.use_count > 1 {
+            details.push(format!("×{}", translation.use_count));
+        }
+        if translation.pinned {
+            details.push(gettext("Pinned"));
+        }
+        
+        details.join(" · ")
+    }
+    
     pub fn show_create_list_dialog(&self) {
         // Get the parent window first
         let parent_window = gtk::Window::list_toplevels()
@@ -425,7 +586,7 @@ impl HistoryPanel {
         
         // Create a standalone dialog instead of a popover
         let dialog = gtk::Dialog::new();
-        dialog.set_title(Some("Create New List"));
+        dialog.set_title(Some(&gettext("Create New List")));
         dialog.set_modal(true);
         dialog.set_transient_for(parent_window.as_ref());
         dialog.set_default_width(350);
@@ -439,12 +600,12 @@ impl HistoryPanel {
         content_area.set_spacing(16);
         
         // Create the content
-        let title = Label::new(Some("Create New List"));
+        let title = Label::new(Some(&gettext("Create New List")));
         title.add_css_class("title-4");
         title.set_halign(gtk::Align::Start);
         
         let name_entry = Entry::new();
-        name_entry.set_placeholder_text(Some("Enter list name"));
+        name_entry.set_placeholder_text(Some(&gettext("Enter list name")));
         name_entry.set_activates_default(true);
         
         // Add to content area
@@ -452,8 +613,8 @@ impl HistoryPanel {
         content_area.append(&name_entry);
         
         // Add buttons
-        dialog.add_button("Cancel", gtk::ResponseType::Cancel);
-        dialog.add_button("Create", gtk::ResponseType::Ok);
+        dialog.add_button(&gettext("Cancel"), gtk::ResponseType::Cancel);
+        dialog.add_button(&gettext("Create"), gtk::ResponseType::Ok);
         dialog.set_default_response(gtk::ResponseType::Ok);
         
         // Connect response signal
@@ -473,7 +634,7 @@ impl HistoryPanel {
                         parent_window.as_ref(),
                         gtk::MessageType::Error,
                         gtk::ButtonsType::Ok,
-                        "Please enter a list name"
+                        &gettext("Please enter a list name")
                     );
                     return;
                 }
@@ -492,7 +653,7 @@ impl HistoryPanel {
                                 parent_window.as_ref(),
                                 gtk::MessageType::Error,
                                 gtk::ButtonsType::Ok,
-                                &format!("A list named \"{}\" already exists. Please choose a different name.", name)
+                                &gettext_f("A list named \"{}\" already exists. Please choose a different name.", &[&name])
                             );
                             return;
                         } else {
@@ -517,7 +678,7 @@ impl HistoryPanel {
                             parent_window.as_ref(),
                             gtk::MessageType::Error,
                             gtk::ButtonsType::Ok,
-                            &format!("Database error: {}", e)
+                            &gettext_f("Database error: {}", &[&e.to_string()])
                         );
                         return;
                     }
@@ -531,134 +692,132 @@ impl HistoryPanel {
         dialog.show();
     }
     
+    /// Selected row and the ID of the translation it shows
+    fn selected_translation(&self) -> Option<(ListBoxRow, i64)> {
+        let row = self.translation_list.selected_row()?;
+        let translation_id = row.widget_name().parse::<i64>().ok()?;
+        Some((row, translation_id))
+    }
+    
     fn reuse_selected_translation(&self) {
-        if let Some(row) = self.translation_list.selected_row() {
-            let widget_id = row.widget_name().to_string().parse::<u32>().unwrap_or(0);
-            if let Some(translation_id) = self.translation_id_map.borrow().get(&widget_id) {
-                if let Ok(translations) = self.db.borrow().get_translations(100) {
-                    if let Some(translation) = translations.iter().find(|t| t.id == *translation_id) {
-                        self.input_buffer.borrow().set_text(&translation.source_text);
-                        self.output_buffer.borrow().set_text(&translation.target_text);
+        if let Some((_, translation_id)) = self.selected_translation() {
+            if let Ok(Some(translation)) = self.db.borrow().get_translation_by_id(translation_id) {
+                self.input_buffer.borrow().set_text(&translation.source_text);
+                self.output_buffer.borrow().set_text(&translation.target_text);
                         
-                        // Set the language combo boxes
-                        self.source_lang.borrow().set_active_id(Some(&translation.source_lang));
-                        self.target_lang.borrow().set_active_id(Some(&translation.target_lang));
-                    }
-                }
+                // Set the language combo boxes
+                self.source_lang.borrow().set_active_id(Some(&translation.source_lang));
+                self.target_lang.borrow().set_active_id(Some(&translation.target_lang));
+            }
+        }
+    }
+    
+    fn toggle_selected_pin(&self) {
+        if let Some((_, translation_id)) = self.selected_translation() {
+            let pinned = match self.db.borrow().get_translation_by_id(translation_id) {
+                Ok(Some(translation)) => translation.pinned,
+                _ => return,
+            };
+            
+            if self.db.borrow().set_pinned(translation_id, !pinned).is_ok() {
+                self.reload();
             }
         }
     }
     
     fn add_selected_to_list(&self) {
-        if let Some(row) = self.translation_list.selected_row() {
-            let widget_id = row.widget_name().to_string().parse::<u32>().unwrap_or(0);
-            if let Some(translation_id) = self.translation_id_map.borrow().get(&widget_id) {
-                let translation_id_actual = *translation_id;
-                // Create a popover for list selection
-                let popover = Popover::new();
-                popover.set_position(gtk::PositionType::Bottom);
-                popover.set_parent(&self.translation_list);
+        if let Some((_, translation_id)) = self.selected_translation() {
+            // Create a popover for list selection
+            let popover = Popover::new();
+            popover.set_position(gtk::PositionType::Bottom);
+            popover.set_parent(&self.translation_list);
                 
-                // Create the dialog content
-                let dialog_box = GtkBox::new(Orientation::Vertical, 10);
-                dialog_box.set_margin_start(10);
-                dialog_box.set_margin_end(10);
-                dialog_box.set_margin_top(10);
-                dialog_box.set_margin_bottom(10);
+            // Create the dialog content
+            let dialog_box = GtkBox::new(Orientation::Vertical, 10);
+            dialog_box.set_margin_start(10);
+            dialog_box.set_margin_end(10);
+            dialog_box.set_margin_top(10);
+            dialog_box.set_margin_bottom(10);
                 
-                let title = Label::new(Some("Add to List"));
-                title.add_css_class("title-4");
+            let title = Label::new(Some(&gettext("Add to List")));
+            title.add_css_class("title-4");
                 
-                let list_combo = ComboBoxText::new();
+            let list_combo = ComboBoxText::new();
                 
-                // Add lists from the database
-                if let Ok(lists) = self.db.borrow().get_lists() {
-                    for list in lists {
-                        list_combo.append(Some(&list.id.to_string()), &list.name);
-                    }
+            // Add lists from the database
+            if let Ok(lists) = self.db.borrow().get_lists() {
+                for list in lists {
+                    list_combo.append(Some(&list.id.to_string()), &list.name);
                 }
+            }
                 
-                let button_box = GtkBox::new(Orientation::Horizontal, 5);
-                button_box.set_halign(gtk::Align::End);
+            let button_box = GtkBox::new(Orientation::Horizontal, 5);
+            button_box.set_halign(gtk::Align::End);
                 
-                let cancel_button = Button::with_label("Cancel");
-                let add_button = Button::with_label("Add");
-                add_button.add_css_class("suggested-action");
+            let cancel_button = Button::with_label(&gettext("Cancel"));
+            let add_button = Button::with_label(&gettext("Add"));
+            add_button.add_css_class("suggested-action");
                 
-                button_box.append(&cancel_button);
-                button_box.append(&add_button);
+            button_box.append(&cancel_button);
+            button_box.append(&add_button);
                 
-                dialog_box.append(&title);
-                dialog_box.append(&list_combo);
-                dialog_box.append(&button_box);
+            dialog_box.append(&title);
+            dialog_box.append(&list_combo);
+            dialog_box.append(&button_box);
                 
-                popover.set_child(Some(&dialog_box));
+            popover.set_child(Some(&dialog_box));
                 
-                // Connect cancel button
-                let popover_ref = popover.clone();
-                cancel_button.connect_clicked(move |_| {
-                    popover_ref.popdown();
-                });
+            // Connect cancel button
+            let popover_ref = popover.clone();
+            cancel_button.connect_clicked(move |_| {
+                popover_ref.popdown();
+            });
                 
-                // Connect add button
-                let self_ref = self.clone();
-                let popover_ref = popover.clone();
-                let list_combo_ref = list_combo.clone();
-                let translation_id_val = translation_id_actual; 
-                add_button.connect_clicked(move |_| {
-                    if let Some(list_id_str) = list_combo_ref.active_id() {
-                        if let Ok(list_id) = list_id_str.to_string().parse::<i64>() {
-                            // Add the translation to the list
-                            let _ = self_ref.db.borrow().add_to_list(list_id, translation_id_val);
+            // Connect add button
+            let self_ref = self.clone();
+            let popover_ref = popover.clone();
+            let list_combo_ref = list_combo.clone();
+            add_button.connect_clicked(move |_| {
+                if let Some(list_id_str) = list_combo_ref.active_id() {
+                    if let Ok(list_id) = list_id_str.to_string().parse::<i64>() {
+                        // Add the translation to the list
+                        let _ = self_ref.db.borrow().add_to_list(list_id, translation_id);
                             
-                            // If we're currently viewing this list, refresh it
-                            if let Some(active_list_id) = *self_ref.active_list_id.borrow() {
-                                if active_list_id == list_id {
-                                    self_ref.load_list_translations(list_id);
-                                }
+                        // If we're currently viewing this list, refresh it
+                        if let Some(active_list_id) = *self_ref.active_list_id.borrow() {
+                            if active_list_id == list_id {
+                                self_ref.load_list_translations(list_id);
                             }
                         }
                     }
-                    popover_ref.popdown();
-                });
+                }
+                popover_ref.popdown();
+            });
                 
-                // Show the popover
-                popover.popup();
-            }
+            // Show the popover
+            popover.popup();
         }
     }
     
     fn delete_selected_translation(&self) {
-        if let Some(row) = self.translation_list.selected_row() {
-            let widget_id = row.widget_name().to_string().parse::<u32>().unwrap_or(0);
+        if let Some((row, translation_id)) = self.selected_translation() {
+            let result = self.db.borrow().delete_translation(translation_id);
             
-            // Use a scope to limit the lifetime of the borrow
-            let translation_id_opt = {
-                let map = self.translation_id_map.borrow();
-                map.get(&widget_id).copied()
-            };
+            if result.is_ok() {
+                self.translation_list.remove(&row);
+            } else {
+                // Show error dialog using our helper function
+                let parent_window = gtk::Window::list_toplevels()
+                    .into_iter()
+                    .find(|w| w.is_visible() && w.widget_name().as_str() != "GtkPopoverWindow")
+                    .and_then(|w| w.downcast::<gtk::Window>().ok());
             
-            if let Some(translation_id) = translation_id_opt {
-                // Now we can safely borrow mutably
-                let result = self.db.borrow().delete_translation(translation_id);
-                
-                if result.is_ok() {
-                    self.translation_id_map.borrow_mut().remove(&widget_id);
-                    self.translation_list.remove(&row);
-                } else {
-                    // Show error dialog using our helper function
-                    let parent_window = gtk::Window::list_toplevels()
-                        .into_iter()
-                        .find(|w| w.is_visible() && w.widget_name().as_str() != "GtkPopoverWindow")
-                        .and_then(|w| w.downcast::<gtk::Window>().ok());
-                    
-                    show_message_dialog(
-                        parent_window.as_ref(),
-                        MessageType::Error,
-                        ButtonsType::Ok,
-                        "Error deleting translation"
-                    );
-                }
+                show_message_dialog(
+                    parent_window.as_ref(),
+                    MessageType::Error,
+                    ButtonsType::Ok,
+                    &gettext("Error deleting translation")
+                );
             }
         }
     }
@@ -690,12 +849,12 @@ impl HistoryPanel {
                     if let Some(parent) = parent_window {
                         // Create file chooser dialog
                         let dialog = gtk::FileChooserDialog::new(
-                            Some("Export List"),
+                            Some(&gettext("Export List")),
                             Some(&parent),
                             gtk::FileChooserAction::Save,
                             &[
-                                ("Cancel", gtk::ResponseType::Cancel),
-                                ("Save", gtk::ResponseType::Accept),
+                                (&gettext("Cancel"), gtk::ResponseType::Cancel),
+                                (&gettext("Save"), gtk::ResponseType::Accept),
                             ],
                         );
 
@@ -704,13 +863,13 @@ impl HistoryPanel {
 
                         // Add CSV file filter
                         let filter = gtk::FileFilter::new();
-                        filter.set_name(Some("CSV Files"));
+                        filter.set_name(Some(&gettext("CSV Files")));
                         filter.add_pattern("*.csv");
                         dialog.add_filter(&filter);
 
                         // Add "All Files" filter
                         let all_filter = gtk::FileFilter::new();
-                        all_filter.set_name(Some("All Files"));
+                        all_filter.set_name(Some(&gettext("All Files")));
                         all_filter.add_pattern("*");
                         dialog.add_filter(&all_filter);
 
@@ -736,8 +895,8 @@ impl HistoryPanel {
                                                 gtk::DialogFlags::MODAL,
                                                 gtk::MessageType::Question,
                                                 gtk::ButtonsType::YesNo,
-                                                &format!("File '{}' already exists. Do you want to overwrite it?", 
-                                                    path.file_name().unwrap_or_default().to_string_lossy())
+                                                &gettext_f("File '{}' already exists. Do you want to overwrite it?", 
+                                                    &[&path.file_name().unwrap_or_default().to_string_lossy()])
                                             );
                                             
                                             let path_clone = path.clone();
@@ -786,7 +945,7 @@ impl HistoryPanel {
                             Some(&parent),
                             MessageType::Error,
                             ButtonsType::Ok,
-                            &format!("Error exporting data: {}", err)
+                            &gettext_f("Error exporting data: {}", &[&err.to_string()])
                         );
                     } else {
                         eprintln!("Error exporting data: {}", err);
@@ -805,7 +964,7 @@ impl HistoryPanel {
                     Some(&parent),
                     MessageType::Info,
                     ButtonsType::Ok,
-                    "Please select a list to export"
+                    &gettext("Please select a list to export")
                 );
             } else {
                 eprintln!("Please select a list to export");
@@ -813,6 +972,11 @@ impl HistoryPanel {
         }
     }
 
+    /// Set how many entries the history view shows, -1 for all
+    pub fn set_view_limit(&self, limit: i64) {
+        self.view_limit.set(limit);
+    }
+    
     // Add a method to update the view when translation state changes
     pub fn update_view(&self) {
         // Reset active list when switching to "all translations" mode
@@ -837,7 +1001,7 @@ impl HistoryPanel {
                 lists.iter()
                     .find(|list| list.id == list_id)
                     .map(|list| list.name.clone())
-                    .unwrap_or_else(|| "Unknown List".to_string())
+                    .unwrap_or_else(|| gettext("Unknown List"))
             };
             
             // Get a reference to the parent window for dialogs
@@ -852,7 +1016,7 @@ impl HistoryPanel {
                 gtk::DialogFlags::MODAL,
                 gtk::MessageType::Question,
                 gtk::ButtonsType::YesNo,
-                &format!("Are you sure you want to delete the list \"{}\"?\nThis action cannot be undone.", list_name)
+                &gettext_f("Are you sure you want to delete the list \"{}\"?\nThis action cannot be undone.", &[&list_name])
             );
             
             // Store required data to avoid borrowing conflicts in the callback
@@ -888,7 +1052,7 @@ impl HistoryPanel {
                                 parent_window.as_ref(),
                                 gtk::MessageType::Info,
                                 gtk::ButtonsType::Ok,
-                                &format!("List \"{}\" deleted successfully", list_name_value)
+                                &gettext_f("List \"{}\" deleted successfully", &[&list_name_value])
                             );
                         },
                         Err(e) => {
@@ -902,7 +1066,7 @@ impl HistoryPanel {
                                 parent_window.as_ref(),
                                 gtk::MessageType::Error,
                                 gtk::ButtonsType::Ok,
-                                &format!("Error deleting list: {}", e)
+                                &gettext_f("Error deleting list: {}", &[&e.to_string()])
                             );
                         }
                     }
@@ -922,16 +1086,16 @@ impl HistoryPanel {
                 parent_window.as_ref(),
                 gtk::MessageType::Info,
                 gtk::ButtonsType::Ok,
-                "Please select a list to delete"
+                &gettext("Please select a list to delete")
             );
         }
     }
 }
 
 fn show_success_dialog(parent: &gtk::Window, path: &std::path::Path) {
-    let message = format!(
+    let message = gettext_f(
         "List exported successfully to:\n\n{}\n\nWould you like to open the folder?", 
-        path.display()
+        &[&path.display().to_string()]
     );
     
     let success_dialog = gtk::MessageDialog::new(
@@ -961,6 +1125,6 @@ fn show_error_dialog(parent: &gtk::Window, error_message: &str) {
         Some(parent),
         MessageType::Error,
         ButtonsType::Ok,
-        &format!("Error writing file: {}", error_message)
+        &gettext_f("Error writing file: {}", &[error_message])
     );
 }
\ No newline at end of file
@@ -0,0 +1,129 @@
+use gettextrs::{bind_textdomain_codeset, bindtextdomain, gettext, setlocale, textdomain, LocaleCategory};
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// Gettext domain of the interface strings, also the .mo file name
+pub const GETTEXT_PACKAGE: &str = "swiftlingo";
+
+/// Catalog directory compiled by build.rs, used when running from the build tree
+const BUILD_LOCALE_DIR: Option<&str> = option_env!("SWIFTLINGO_BUILD_LOCALEDIR");
+
+/// Languages with a translation catalog, from po/LINGUAS
+const LINGUAS: &str = include_str!("../po/LINGUAS");
+
+/// Set up gettext for the interface
+///
+/// `ui_language` overrides the system locale with a catalog language such as
+/// "de" or "pt_BR". Must run before any window is created.
+pub fn init(ui_language: Option<&str>) {
+    let ui_language = ui_language.filter(|language| !language.is_empty());
+    if let Some(language) = ui_language {
+        // LANGUAGE takes precedence over LC_ALL and LANG for message lookup
+        env::set_var("LANGUAGE", language);
+    }
+    
+    setlocale(LocaleCategory::LcAll, "");
+    
+    // GNU gettext ignores LANGUAGE while messages use the C locale
+    if let Some(language) = ui_language {
+        let messages_locale = setlocale(LocaleCategory::LcMessages, "");
+        if messages_locale.as_deref().map_or(true, |name| name == b"C" || name == b"POSIX") {
+            use_utf8_messages_locale(language);
+        }
+    }
+    
+    let locale_dir = locale_dir();
+    if let Err(e) = bindtextdomain(GETTEXT_PACKAGE, &locale_dir) {
+        eprintln!("Could not bind the {} catalogs in {}: {}", GETTEXT_PACKAGE, locale_dir.display(), e);
+    }
+    let _ = bind_textdomain_codeset(GETTEXT_PACKAGE, "UTF-8");
+    let _ = textdomain(GETTEXT_PACKAGE);
+}
+
+/// Switch messages to an installed UTF-8 locale, the chosen language's own
+/// or else C.UTF-8, so the LANGUAGE override applies.
+///
+/// The locale goes into the environment too, as GTK sets the locale from it
+/// again during startup. A value in LC_ALL, which would win over
+/// LC_MESSAGES, is moved to LANG.
+fn use_utf8_messages_locale(language: &str) {
+    let candidates = [format!("{}.UTF-8", language), format!("{}.utf8", language), "C.UTF-8".to_string()];
+    
+    let locale = match candidates.iter().find(|locale| setlocale(LocaleCategory::LcMessages, locale.as_str()).is_some()) {
+        Some(locale) => locale,
+        None => {
+            eprintln!("No UTF-8 locale available, the interface language setting has no effect in the C locale");
+            return;
+        }
+    };
+    
+    if let Some(all) = env::var_os("LC_ALL").filter(|all| !all.is_empty()) {
+        env::remove_var("LC_ALL");
+        env::set_var("LANG", all);
+        setlocale(LocaleCategory::LcAll, "");
+    }
+    env::set_var("LC_MESSAGES", locale);
+    setlocale(LocaleCategory::LcMessages, locale.as_str());
+}
+
+/// Directory holding <lang>/LC_MESSAGES/swiftlingo.mo
+fn locale_dir() -> PathBuf {
+    // Step 1: Explicit override
+    if let Ok(dir) = env::var("SWIFTLINGO_LOCALEDIR") {
+        return PathBuf::from(dir);
+    }
+    
+    // Step 2: Installed next to the binary, e.g. /usr/local/bin/../share/locale
+    let installed = env::current_exe().ok()
+        .and_then(|exe| exe.parent().and_then(Path::parent).map(|prefix| prefix.join("share").join("locale")))
+        .filter(|dir| has_catalogs(dir));
+    if let Some(dir) = installed {
+        return dir;
+    }
+    
+    // Step 3: Catalogs compiled during the build
+    if let Some(dir) = BUILD_LOCALE_DIR.map(PathBuf::from).filter(|dir| has_catalogs(dir)) {
+        return dir;
+    }
+    
+    PathBuf::from("/usr/share/locale")
+}
+
+/// Check whether a locale directory holds any of our catalogs
+fn has_catalogs(dir: &Path) -> bool {
+    ui_languages().iter().any(|language| {
+        dir.join(language)
+            .join("LC_MESSAGES")
+            .join(format!("{}.mo", GETTEXT_PACKAGE))
+            .exists()
+    })
+}
+
+/// Languages the interface has been translated to
+pub fn ui_languages() -> Vec<&'static str> {
+    LINGUAS.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .flat_map(str::split_whitespace)
+        .collect()
+}
+
+/// Translate a message and fill its `{}` placeholders in order
+///
+/// Translations must keep the placeholders in the same order as the msgid.
+pub fn gettext_f(msgid: &str, args: &[&str]) -> String {
+    let translated = gettext(msgid);
+    let mut args = args.iter();
+    let mut result = String::with_capacity(translated.len());
+    let mut rest = translated.as_str();
+    
+    // Substitute left to right so braces inside arguments are left alone
+    while let Some(index) = rest.find("{}") {
+        result.push_str(&rest[..index]);
+        result.push_str(args.next().copied().unwrap_or("{}"));
+        rest = &rest[index + 2..];
+    }
+    result.push_str(rest);
+    
+    result
+}
@@ -1,116 +1,237 @@
-use std::collections::HashMap;
-use once_cell::sync::Lazy;
+use gettextrs::gettext;
+use crate::translation::TranslationService;
 
-pub static LANGUAGES: Lazy<HashMap<&str, &str>> = Lazy::new(|| {
-    let mut m = HashMap::new();
-    m.insert("auto", "Detect language");
-    m.insert("af", "Afrikaans");
-    m.insert("sq", "Albanian");
-    m.insert("am", "Amharic");
-    m.insert("ar", "Arabic");
-    m.insert("hy", "Armenian");
-    m.insert("az", "Azerbaijani");
-    m.insert("eu", "Basque");
-    m.insert("be", "Belarusian");
-    m.insert("bn", "Bengali");
-    m.insert("bs", "Bosnian");
-    m.insert("bg", "Bulgarian");
-    m.insert("ca", "Catalan");
-    m.insert("ceb", "Cebuano");
-    m.insert("zh-CN", "Chinese (Simplified)");
-    m.insert("zh-TW", "Chinese (Traditional)");
-    m.insert("co", "Corsican");
-    m.insert("hr", "Croatian");
-    m.insert("cs", "Czech");
-    m.insert("da", "Danish");
-    m.insert("nl", "Dutch");
-    m.insert("en", "English");
-    m.insert("eo", "Esperanto");
-    m.insert("et", "Estonian");
-    m.insert("fi", "Finnish");
-    m.insert("fr", "French");
-    m.insert("fy", "Frisian");
-    m.insert("gl", "Galician");
-    m.insert("ka", "Georgian");
-    m.insert("de", "German");
-    m.insert("el", "Greek");
-    m.insert("gu", "Gujarati");
-    m.insert("ht", "Haitian Creole");
-    m.insert("ha", "Hausa");
-    m.insert("haw", "Hawaiian");
-    m.insert("he", "Hebrew");
-    m.insert("hi", "Hindi");
-    m.insert("hmn", "Hmong");
-    m.insert("hu", "Hungarian");
-    m.insert("is", "Icelandic");
-    m.insert("ig", "Igbo");
-    m.insert("id", "Indonesian");
-    m.insert("ga", "Irish");
-    m.insert("it", "Italian");
-    m.insert("ja", "Japanese");
-    m.insert("jv", "Javanese");
-    m.insert("kn", "Kannada");
-    m.insert("kk", "Kazakh");
-    m.insert("km", "Khmer");
-    m.insert("ko", "Korean");
-    m.insert("ku", "Kurdish");
-    m.insert("ky", "Kyrgyz");
-    m.insert("lo", "Lao");
-    m.insert("la", "Latin");
-    m.insert("lv", "Latvian");
-    m.insert("lt", "Lithuanian");
-    m.insert("lb", "Luxembourgish");
-    m.insert("mk", "Macedonian");
-    m.insert("mg", "Malagasy");
-    m.insert("ms", "Malay");
-    m.insert("ml", "Malayalam");
-    m.insert("mt", "Maltese");
-    m.insert("mi", "Maori");
-    m.insert("mr", "Marathi");
-    m.insert("mn", "Mongolian");
-    m.insert("my", "Myanmar (Burmese)");
-    m.insert("ne", "Nepali");
-    m.insert("no", "Norwegian");
-    m.insert("ny", "Nyanja (Chichewa)");
-    m.insert("or", "Odia (Oriya)");
-    m.insert("ps", "Pashto");
-    m.insert("fa", "Persian");
-    m.insert("pl", "Polish");
-    m.insert("pt", "Portuguese");
-    m.insert("pa", "Punjabi");
-    m.insert("ro", "Romanian");
-    m.insert("ru", "Russian");
-    m.insert("sm", "Samoan");
-    m.insert("gd", "Scots Gaelic");
-    m.insert("sr", "Serbian");
-    m.insert("st", "Sesotho");
-    m.insert("sn", "Shona");
-    m.insert("sd", "Sindhi");
-    m.insert("si", "Sinhala (Sinhalese)");
-    m.insert("sk", "Slovak");
-    m.insert("sl", "Slovenian");
-    m.insert("so", "Somali");
-    m.insert("es", "Spanish");
-    m.insert("su", "Sundanese");
-    m.insert("sw", "Swahili");
-    m.insert("sv", "Swedish");
-    m.insert("tl", "Tagalog (Filipino)");
-    m.insert("tg", "Tajik");
-    m.insert("ta", "Tamil");
-    m.insert("tt", "Tatar");
-    m.insert("te", "Telugu");
-    m.insert("th", "Thai");
-    m.insert("tr", "Turkish");
-    m.insert("tk", "Turkmen");
-    m.insert("uk", "Ukrainian");
-    m.insert("ur", "Urdu");
-    m.insert("ug", "Uyghur");
-    m.insert("uz", "Uzbek");
-    m.insert("vi", "Vietnamese");
-    m.insert("cy", "Welsh");
-    m.insert("xh", "Xhosa");
-    m.insert("yi", "Yiddish");
-    m.insert("yo", "Yoruba");
-    m.insert("zu", "Zulu");
-    m
-});
\ No newline at end of file
+/// Code of the "Detect language" source option
+pub const AUTO: &str = "auto";
+
+/// A language the app can translate, identified by its BCP-47 tag
+#[derive(Debug, Clone, Copy)]
+pub struct Language {
+    pub tag: &'static str,
+    pub english_name: &'static str,
+    pub native_name: &'static str,
+    pub script: &'static str, // ISO 15924 code, e.g. "Latn", "Arab"
+    pub rtl: bool,
+    pub aliases: &'static [&'static str], // Other codes services use for the language
+    pub service_codes: &'static [(TranslationService, &'static str)], // Where a service differs from `tag`
+}
+
+impl Language {
+    const fn new(tag: &'static str, english_name: &'static str, native_name: &'static str, script: &'static str) -> Self {
+        Language {
+            tag,
+            english_name,
+            native_name,
+            script,
+            rtl: false,
+            aliases: &[],
+            service_codes: &[],
+        }
+    }
+    
+    const fn rtl(self) -> Self {
+        Language { rtl: true, ..self }
+    }
+    
+    const fn aliases(self, aliases: &'static [&'static str]) -> Self {
+        Language { aliases, ..self }
+    }
+    
+    const fn service_codes(self, service_codes: &'static [(TranslationService, &'static str)]) -> Self {
+        Language { service_codes, ..self }
+    }
+    
+    /// Name shown in language selectors, e.g. "German — Deutsch"
+    pub fn label(&self) -> String {
+        if self.native_name == self.english_name {
+            self.english_name.to_string()
+        } else {
+            format!("{} — {}", self.english_name, self.native_name)
+        }
+    }
+    
+    /// Code a service expects for this language
+    pub fn code_for(&self, service: &TranslationService) -> &'static str {
+        self.service_codes.iter()
+            .find(|(code_service, _)| code_service == service)
+            .map_or(self.tag, |(_, code)| code)
+    }
+    
+    fn matches(&self, code: &str) -> bool {
+        self.tag.eq_ignore_ascii_case(code)
+            || self.aliases.iter().any(|alias| alias.eq_ignore_ascii_case(code))
+    }
+}
+
+/// Every supported language, ordered by English name
+pub static LANGUAGES: &[Language] = &[
+    Language::new("af", "Afrikaans", "Afrikaans", "Latn"),
+    Language::new("sq", "Albanian", "Shqip", "Latn"),
+    Language::new("am", "Amharic", "አማርኛ", "Ethi"),
+    Language::new("ar", "Arabic", "العربية", "Arab").rtl(),
+    Language::new("hy", "Armenian", "Հայերեն", "Armn"),
+    Language::new("az", "Azerbaijani", "Azərbaycan", "Latn"),
+    Language::new("eu", "Basque", "Euskara", "Latn"),
+    Language::new("be", "Belarusian", "Беларуская", "Cyrl"),
+    Language::new("bn", "Bengali", "বাংলা", "Beng"),
+    Language::new("bs", "Bosnian", "Bosanski", "Latn"),
+    Language::new("bg", "Bulgarian", "Български", "Cyrl"),
+    Language::new("ca", "Catalan", "Català", "Latn"),
+    Language::new("ceb", "Cebuano", "Cebuano", "Latn"),
+    Language::new("zh-CN", "Chinese (Simplified)", "简体中文", "Hans").aliases(&["zh", "zh-Hans", "zh-SG", "cmn"]).service_codes(&[
+        (TranslationService::Bing, "zh-Hans"),
+        (TranslationService::DeepL, "ZH"),
+        (TranslationService::LibreTranslate, "zh"),
+        (TranslationService::Lingva, "zh"),
+    ]),
+    Language::new("zh-TW", "Chinese (Traditional)", "繁體中文", "Hant").aliases(&["zh-Hant", "zh-HK"]).service_codes(&[
+        (TranslationService::Bing, "zh-Hant"),
+        (TranslationService::LibreTranslate, "zt"),
+        (TranslationService::Lingva, "zh_HANT"),
+    ]),
+    Language::new("co", "Corsican", "Corsu", "Latn"),
+    Language::new("hr", "Croatian", "Hrvatski", "Latn"),
+    Language::new("cs", "Czech", "Čeština", "Latn"),
+    Language::new("da", "Danish", "Dansk", "Latn"),
+    Language::new("nl", "Dutch", "Nederlands", "Latn"),
+    Language::new("en", "English", "English", "Latn").service_codes(&[
+        (TranslationService::DeepL, "EN-US"),
+    ]),
+    Language::new("eo", "Esperanto", "Esperanto", "Latn"),
+    Language::new("et", "Estonian", "Eesti", "Latn"),
+    Language::new("fi", "Finnish", "Suomi", "Latn"),
+    Language::new("fr", "French", "Français", "Latn"),
+    Language::new("fy", "Frisian", "Frysk", "Latn"),
+    Language::new("gl", "Galician", "Galego", "Latn"),
+    Language::new("ka", "Georgian", "ქართული", "Geor"),
+    Language::new("de", "German", "Deutsch", "Latn"),
+    Language::new("el", "Greek", "Ελληνικά", "Grek"),
+    Language::new("gu", "Gujarati", "ગુજરાતી", "Gujr"),
+    Language::new("ht", "Haitian Creole", "Kreyòl ayisyen", "Latn"),
+    Language::new("ha", "Hausa", "Hausa", "Latn"),
+    Language::new("haw", "Hawaiian", "ʻŌlelo Hawaiʻi", "Latn"),
+    Language::new("he", "Hebrew", "עברית", "Hebr").rtl().aliases(&["iw"]),
+    Language::new("hi", "Hindi", "हिन्दी", "Deva"),
+    Language::new("hmn", "Hmong", "Hmoob", "Latn"),
+    Language::new("hu", "Hungarian", "Magyar", "Latn"),
+    Language::new("is", "Icelandic", "Íslenska", "Latn"),
+    Language::new("ig", "Igbo", "Igbo", "Latn"),
+    Language::new("id", "Indonesian", "Bahasa Indonesia", "Latn").aliases(&["in"]),
+    Language::new("ga", "Irish", "Gaeilge", "Latn"),
+    Language::new("it", "Italian", "Italiano", "Latn"),
+    Language::new("ja", "Japanese", "日本語", "Jpan"),
+    Language::new("jv", "Javanese", "Basa Jawa", "Latn").aliases(&["jw"]),
+    Language::new("kn", "Kannada", "ಕನ್ನಡ", "Knda"),
+    Language::new("kk", "Kazakh", "Қазақ тілі", "Cyrl"),
+    Language::new("km", "Khmer", "ខ្មែរ", "Khmr"),
+    Language::new("ko", "Korean", "한국어", "Kore"),
+    Language::new("ku", "Kurdish", "Kurdî", "Latn").aliases(&["kmr"]),
+    Language::new("ky", "Kyrgyz", "Кыргызча", "Cyrl"),
+    Language::new("lo", "Lao", "ລາວ", "Laoo"),
+    Language::new("la", "Latin", "Latina", "Latn"),
+    Language::new("lv", "Latvian", "Latviešu", "Latn"),
+    Language::new("lt", "Lithuanian", "Lietuvių", "Latn"),
+    Language::new("lb", "Luxembourgish", "Lëtzebuergesch", "Latn"),
+    Language::new("mk", "Macedonian", "Македонски", "Cyrl"),
+    Language::new("mg", "Malagasy", "Malagasy", "Latn"),
+    Language::new("ms", "Malay", "Bahasa Melayu", "Latn"),
+    Language::new("ml", "Malayalam", "മലയാളം", "Mlym"),
+    Language::new("mt", "Maltese", "Malti", "Latn"),
+    Language::new("mi", "Maori", "Te Reo Māori", "Latn"),
+    Language::new("mr", "Marathi", "मराठी", "Deva"),
+    Language::new("mn", "Mongolian", "Монгол", "Cyrl"),
+    Language::new("my", "Myanmar (Burmese)", "မြန်မာ", "Mymr"),
+    Language::new("ne", "Nepali", "नेपाली", "Deva"),
+    Language::new("no", "Norwegian", "Norsk", "Latn").aliases(&["nb"]).service_codes(&[
+        (TranslationService::Bing, "nb"),
+        (TranslationService::DeepL, "NB"),
+    ]),
+    Language::new("ny", "Nyanja (Chichewa)", "Chichewa", "Latn"),
+    Language::new("or", "Odia (Oriya)", "ଓଡ଼ିଆ", "Orya"),
+    Language::new("ps", "Pashto", "پښتو", "Arab").rtl(),
+    Language::new("fa", "Persian", "فارسی", "Arab").rtl(),
+    Language::new("pl", "Polish", "Polski", "Latn"),
+    Language::new("pt", "Portuguese", "Português", "Latn").service_codes(&[
+        (TranslationService::DeepL, "PT-BR"),
+    ]),
+    Language::new("pa", "Punjabi", "ਪੰਜਾਬੀ", "Guru"),
+    Language::new("ro", "Romanian", "Română", "Latn"),
+    Language::new("ru", "Russian", "Русский", "Cyrl"),
+    Language::new("sm", "Samoan", "Gagana Samoa", "Latn"),
+    Language::new("gd", "Scots Gaelic", "Gàidhlig", "Latn"),
+    Language::new("sr", "Serbian", "Српски", "Cyrl"),
+    Language::new("st", "Sesotho", "Sesotho", "Latn"),
+    Language::new("sn", "Shona", "chiShona", "Latn"),
+    Language::new("sd", "Sindhi", "سنڌي", "Arab").rtl(),
+    Language::new("si", "Sinhala (Sinhalese)", "සිංහල", "Sinh"),
+    Language::new("sk", "Slovak", "Slovenčina", "Latn"),
+    Language::new("sl", "Slovenian", "Slovenščina", "Latn"),
+    Language::new("so", "Somali", "Soomaali", "Latn"),
+    Language::new("es", "Spanish", "Español", "Latn"),
+    Language::new("su", "Sundanese", "Basa Sunda", "Latn"),
+    Language::new("sw", "Swahili", "Kiswahili", "Latn"),
+    Language::new("sv", "Swedish", "Svenska", "Latn"),
+    Language::new("tl", "Tagalog (Filipino)", "Tagalog", "Latn").aliases(&["fil"]).service_codes(&[
+        (TranslationService::Bing, "fil"),
+    ]),
+    Language::new("tg", "Tajik", "Тоҷикӣ", "Cyrl"),
+    Language::new("ta", "Tamil", "தமிழ்", "Taml"),
+    Language::new("tt", "Tatar", "Татарча", "Cyrl"),
+    Language::new("te", "Telugu", "తెలుగు", "Telu"),
+    Language::new("th", "Thai", "ไทย", "Thai"),
+    Language::new("tr", "Turkish", "Türkçe", "Latn"),
+    Language::new("tk", "Turkmen", "Türkmençe", "Latn"),
+    Language::new("uk", "Ukrainian", "Українська", "Cyrl"),
+    Language::new("ur", "Urdu", "اردو", "Arab").rtl(),
+    Language::new("ug", "Uyghur", "ئۇيغۇرچە", "Arab").rtl(),
+    Language::new("uz", "Uzbek", "Oʻzbekcha", "Latn"),
+    Language::new("vi", "Vietnamese", "Tiếng Việt", "Latn"),
+    Language::new("cy", "Welsh", "Cymraeg", "Latn"),
+    Language::new("xh", "Xhosa", "isiXhosa", "Latn"),
+    Language::new("yi", "Yiddish", "ייִדיש", "Hebr").rtl().aliases(&["ji"]),
+    Language::new("yo", "Yoruba", "Yorùbá", "Latn"),
+    Language::new("zu", "Zulu", "isiZulu", "Latn"),
+];
+
+/// Look up a language by its tag or an alias. Unknown regional variants such
+/// as "pt-BR" or "en_GB" fall back to their base language.
+pub fn find(code: &str) -> Option<&'static Language> {
+    LANGUAGES.iter()
+        .find(|language| language.matches(code))
+        .or_else(|| {
+            let base = code.split(['-', '_']).next()?;
+            LANGUAGES.iter().find(|language| language.matches(base))
+        })
+}
+
+/// Canonical tag for a code, or the code unchanged if it is not known
+pub fn normalize(code: &str) -> String {
+    find(code).map_or_else(|| code.to_string(), |language| language.tag.to_string())
+}
+
+/// English name for a code, or the code itself if it is not known.
+/// The "Detect language" option is shown in the interface language.
+pub fn display_name(code: &str) -> String {
+    if code == AUTO {
+        return gettext("Detect language");
+    }
+    
+    find(code).map_or(code, |language| language.english_name).to_string()
+}
+
+/// Code a service expects for a language, or the code unchanged if it is not known
+pub fn service_code<'a>(code: &'a str, service: &TranslationService) -> &'a str {
+    find(code).map_or(code, |language| language.code_for(service))
+}
+
+/// Whether a language is written right to left
+pub fn is_rtl(code: &str) -> bool {
+    find(code).map_or(false, |language| language.rtl)
+}
+
+/// Wrap text in Unicode first-strong isolate marks, so that mixed-direction
+/// content takes its direction from its own first letter and does not
+/// reorder the text around it
+pub fn isolate(text: &str) -> String {
+    format!("\u{2068}{}\u{2069}", text)
+}
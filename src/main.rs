@@ -8,30 +8,50 @@ mod history_ui;
 mod settings_ui;
 mod window_manager; 
 mod ui_helpers;  
+mod dictionary;
+mod transliteration;
+mod tts;
+mod dictation;
+mod secrets;
+mod usage;
+mod routing;
+mod health;
+mod google_auth;
+mod pseudo;
+mod detection;
+mod i18n;
+mod temp_file;
 
 use gtk::prelude::*;
 use gtk::{Application, ApplicationWindow, Box as GtkBox, Button, ComboBoxText, ScrolledWindow};
 use gtk::{Label, Orientation, TextView, TextBuffer, HeaderBar, Statusbar, Frame};
-use gtk::{MenuButton, PopoverMenu, gio, Notebook, Popover, ResponseType};
+use gtk::{MenuButton, PopoverMenu, gio, Notebook, Popover, ResponseType, Expander, ToggleButton};
 use gtk::glib;
 use tokio::runtime::Runtime;
-use languages::LANGUAGES;
 use selection::get_selected_text;
 use hotkey::start_global_hotkey_service;
-use database::Database;
-use translation::TranslationManager;
+use database::{Database, RetentionPolicy, TranslationMetadata, TranslationOrigin};
+use translation::{TranslationManager, TranslationResult, TranslationService};
 use settings::Settings;
 use history_ui::HistoryPanel;
 use settings_ui::SettingsDialog;
 use window_manager::WindowManager;
+use dictation::Recording;
+use secrets::SecretStore;
+use temp_file::TempFile;
+use usage::BudgetStatus;
 use ui_helpers::*;
+use i18n::gettext_f;
+use gettextrs::gettext;
 
 // No command import needed here
 use std::fs;
 use std::env;
-use std::time::{SystemTime, Duration};
+use std::time::{SystemTime, Duration, Instant};
+use std::path::Path;
 use std::rc::Rc;
 use std::cell::RefCell;
+use std::sync::{Arc, Mutex};
 use gtk::glib::source::Continue;
 use gtk::Inhibit;
 
@@ -39,18 +59,33 @@ use gtk::Inhibit;
 const WINDOW_TITLE: &str = "SwiftLingo";
 const APP_ID: &str = "org.swiftlingo.app";
 
+// How often DeepL's own usage count is fetched while a DeepL key is set
+const DEEPL_USAGE_POLL_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
 /// Structure to hold all the shared state and reduce cloning
 struct AppState {
     translation_manager: TranslationManager,
     db: Database,
     input_buffer: TextBuffer,
     output_buffer: TextBuffer,
+    // Progress or error text shown in the output instead of a translation
+    output_message: Option<String>,
+    source_transliteration_label: Label,
+    transliteration_label: Label,
+    pivot_label: Label,
+    service_label: Label,
+    dictionary_expander: Expander,
+    dictionary_label: Label,
     source_lang: ComboBoxText,
     target_lang: ComboBoxText,
     status_bar: Statusbar,
     history_panel: HistoryPanel,
     settings: Settings,
     window_manager: WindowManager,
+    // Playing speech and its audio file, deleted when playback is replaced
+    tts_player: Option<(gtk::MediaFile, TempFile)>,
+    recording: Option<Recording>,
+    retention: Arc<Mutex<RetentionPolicy>>,
 }
 
 /// Asynchronously translates text using the selected translation service
@@ -59,33 +94,97 @@ async fn translate_text(
     source_lang: String, 
     target_lang: String,
     translation_manager: &TranslationManager,
-) -> String {
-    if text.is_empty() {
-        return String::from("Please enter some text to translate");
+) -> Result<TranslationResult, String> {
+    // Use the translation manager, which falls back to another service if the primary one fails
+    translation_manager.translate_with_fallback(&text, &source_lang, &target_lang).await
+        .map_err(|error| gettext_f("Translation error: {}", &[&error.to_string()]))
+}
+
+/// Characters a service has been billed for this month
+fn monthly_usage(db: &Database, service: &TranslationService) -> u64 {
+    db.get_usage(service.config_name(), &usage::current_month())
+        .unwrap_or(0)
+        .max(0) as u64
+}
+
+/// Compare a service's usage this month with its budget
+fn budget_status(state: &AppState, service: &TranslationService) -> BudgetStatus {
+    BudgetStatus::of(
+        monthly_usage(&state.db, service),
+        state.settings.monthly_budget(service),
+        state.settings.usage_warning_percent,
+    )
+}
+
+/// Services that have used up their monthly budget, if switching away from them is enabled
+fn services_over_budget(state: &AppState) -> Vec<TranslationService> {
+    if !state.settings.switch_service_over_budget {
+        return Vec::new();
     }
     
-    // Use the translation manager to translate the text
-    match translation_manager.translate(&text, &source_lang, &target_lang).await {
-        Ok(translation) => translation,
-        Err(error) => {
-            // Try with fallback if the primary service fails
-            match translation_manager.translate_with_fallback(&text, &source_lang, &target_lang).await {
-                Ok(translation) => translation,
-                Err(_) => format!("Translation error: {}", error),
-            }
-        }
+    TranslationService::all_services()
+        .into_iter()
+        .filter(|service| budget_status(state, service) == BudgetStatus::Exceeded)
+        .collect()
+}
+
+/// Status bar warning for a service close to or over its monthly budget
+fn budget_warning(state: &AppState, service: &TranslationService) -> Option<String> {
+    let budget = state.settings.monthly_budget(service)?;
+    let used = monthly_usage(&state.db, service);
+    
+    match budget_status(state, service) {
+        BudgetStatus::Warning => Some(gettext_f(
+            // xgettext:no-c-format
+            "{} has used {}% of its monthly budget ({} of {} characters)",
+            &[&service.to_string(), &(used * 100 / budget).to_string(), &usage::format_characters(used), &usage::format_characters(budget)]
+        )),
+        BudgetStatus::Exceeded => Some(gettext_f(
+            "{} has used up its monthly budget of {} characters",
+            &[&service.to_string(), &usage::format_characters(budget)]
+        )),
+        _ => None,
     }
 }
 
-/// Helper function to perform translation and update UI accordingly
+/// Fetch the characters DeepL has billed this period into the usage records,
+/// so budget warnings and switching use the real figures
+fn poll_deepl_usage(app_state: &Rc<RefCell<AppState>>) {
+    let (config, db) = {
+        let state = app_state.borrow();
+        (state.settings.get_service_config(&TranslationService::DeepL).unwrap_or_default(), state.db.clone())
+    };
+    
+    if config.api_key.as_deref().map_or(true, str::is_empty) {
+        return;
+    }
+    
+    spawn_local_task(move || async move {
+        match usage::fetch_deepl_usage(&config).await {
+            Ok(reported) => {
+                if let Err(e) = db.sync_reported_usage("DeepL", &usage::current_month(), reported.characters as i64) {
+                    eprintln!("Failed to record DeepL usage: {}", e);
+                }
+            },
+            Err(e) => eprintln!("Could not fetch DeepL usage: {}", e),
+        }
+    });
+}
+
+/// Helper function to perform translation and update UI accordingly.
+///
+/// `source_hint` is the language the text is known to be in, such as the one
+/// recognized along with speech, and replaces an "auto" source.
 fn perform_translation(
     text: String,
+    origin: TranslationOrigin,
+    source_hint: Option<&str>,
     app_state: &Rc<RefCell<AppState>>,
 ) {
     let state = app_state.borrow();
     
     if text.is_empty() {
-        state.status_bar.push(0, "No text to translate");
+        state.status_bar.push(0, &gettext("No text to translate"));
         return;
     }
     
@@ -93,46 +192,134 @@ fn perform_translation(
     let source_lang = state.source_lang.active_id()
         .unwrap_or_else(|| gtk::glib::GString::from("auto"))
         .to_string();
-    let target_lang = state.target_lang.active_id()
+    let mut target_lang = state.target_lang.active_id()
         .unwrap_or_else(|| gtk::glib::GString::from("es"))
         .to_string();
     
+    // A known language beats guessing it from the text
+    let source_hint = source_hint.filter(|_| source_lang == "auto");
+    let detected_source = source_hint.unwrap_or_else(|| detection::resolve_auto(&text, &source_lang));
+    
+    // Text already in the target language goes to the other of the primary
+    // and secondary languages instead
+    if let Some(swapped) = state.settings.swapped_target(detected_source, &target_lang) {
+        state.target_lang.set_active_id(Some(&swapped));
+        target_lang = swapped;
+    }
+    
+    // Keep the detected language for the history entry
+    let detected_lang = (detected_source != source_lang).then(|| detected_source.to_string());
+    
     // Show "Translating..." in the output field
-    state.output_buffer.set_text("Translating...");
-    state.status_bar.push(0, "Translating...");
+    state.output_buffer.set_text(&gettext("Translating..."));
+    state.source_transliteration_label.set_visible(false);
+    state.transliteration_label.set_visible(false);
+    state.pivot_label.set_visible(false);
+    state.service_label.set_visible(false);
+    state.dictionary_expander.set_visible(false);
+    state.status_bar.push(0, &gettext("Translating..."));
+    
+    // Clone the manager so no borrow of the app state is held across the request
+    let mut translation_manager = state.translation_manager.clone();
+    
+    // Route around services that are over their monthly budget
+    translation_manager.exclude_services(services_over_budget(&state));
     
     // Drop the borrow before async operation
     drop(state);
+    app_state.borrow_mut().output_message = Some(gettext("Translating..."));
     
     // Clone app_state for the async task
     let app_state_clone = app_state.clone();
     let text_to_translate = text.clone();
     let source_lang_clone = source_lang.clone();
+    let request_source_lang = source_hint.unwrap_or(&source_lang).to_string();
     let target_lang_clone = target_lang.clone();
     
     // Spawn the translation task
     spawn_local_task(move || async move {
-        let translation = {
-            let state = app_state_clone.borrow();
-            translate_text(
-                text_to_translate.clone(), 
-                source_lang_clone.clone(), 
-                target_lang_clone.clone(),
-                &state.translation_manager
-            ).await
-        };
+        let started = Instant::now();
+        let result = translate_text(
+            text_to_translate.clone(), 
+            request_source_lang, 
+            target_lang_clone.clone(),
+            &translation_manager
+        ).await;
+        
+        app_state_clone.borrow_mut().output_message = result.as_ref().err().cloned();
         
         // Now update UI
         let state = app_state_clone.borrow();
-        state.output_buffer.set_text(&translation);
-        state.status_bar.push(0, "Translation complete");
+        let translation = match result {
+            Ok(translation) => translation,
+            Err(error) => {
+                state.output_buffer.set_text(&error);
+                state.status_bar.push(0, &gettext("Translation failed"));
+                return;
+            }
+        };
+        
+        state.output_buffer.set_text(&translation.text);
+        
+        // Show the intermediate text when the pair was routed through a pivot language
+        if let Some(pivot) = &translation.pivot {
+            let pivot_name = languages::display_name(&pivot.language);
+            state.pivot_label.set_text(&gettext_f("Translated via {}: {}", &[&pivot_name, &pivot.text]));
+            state.pivot_label.set_visible(true);
+            state.status_bar.push(0, &gettext_f("Translation complete (via {})", &[&pivot_name]));
+        } else {
+            state.status_bar.push(0, &gettext("Translation complete"));
+        }
+        
+        // Show the romanized readings when enabled
+        let show_transliteration = state.settings.show_transliteration;
+        set_transliteration(&state.source_transliteration_label, translation.source_transliteration.as_deref(), show_transliteration);
+        set_transliteration(&state.transliteration_label, translation.transliteration.as_deref(), show_transliteration);
+        
+        // Show dictionary data for single words and short phrases
+        if let Some(dictionary) = &translation.dictionary {
+            state.dictionary_label.set_markup(&dictionary.to_markup());
+            state.dictionary_expander.set_visible(true);
+        }
+        
+        // Count the characters billed by the service that answered
+        if let Some(service) = &translation.service {
+            let _ = state.db.record_usage(
+                service.config_name(),
+                &usage::current_month(),
+                translation.billed_characters as i64
+            );
+            
+            // Show which service answered and the routing rule that chose it
+            let service_text = match &translation.routing_rule {
+                Some(rule) => gettext_f("Translated with {} (rule: {})", &[&service.to_string(), &rule.to_string()]),
+                None => gettext_f("Translated with {}", &[&service.to_string()]),
+            };
+            state.service_label.set_text(&service_text);
+            state.service_label.set_visible(true);
+            
+            if let Some(warning) = budget_warning(&state, service) {
+                state.status_bar.push(0, &warning);
+            }
+        }
+        
+        // Add to database unless history is paused
+        if !state.settings.auto_save_history {
+            return;
+        }
         
-        // Add to database
+        let metadata = TranslationMetadata {
+            service: translation.service.as_ref().map(|service| service.config_name().to_string()),
+            detected_lang,
+            latency_ms: Some(started.elapsed().as_millis() as i64),
+            origin,
+        };
         let _ = state.db.add_translation(
             &text_to_translate,
             &source_lang_clone,
-            &translation,
-            &target_lang_clone
+            &translation.text,
+            &target_lang_clone,
+            &metadata
         );
         
         // Update history panel
@@ -140,6 +327,252 @@ fn perform_translation(
     });
 }
 
+/// Speak text with the configured TTS engine and play the audio.
+///
+/// GTK plays media files through its GStreamer backend.
+fn speak_text(text: String, lang: String, app_state: &Rc<RefCell<AppState>>) {
+    let options = {
+        let mut state = app_state.borrow_mut();
+        
+        if text.is_empty() || text == gettext("Translating...") {
+            state.status_bar.push(0, &gettext("No text to speak"));
+            return;
+        }
+        
+        // Stop any previous playback before its audio file is deleted
+        if let Some((player, _audio)) = state.tts_player.take() {
+            player.pause();
+        }
+        
+        state.status_bar.push(0, &gettext("Synthesizing speech..."));
+        state.settings.tts_options()
+    };
+    
+    let app_state_clone = app_state.clone();
+    spawn_local_task(move || async move {
+        let result = tts::synthesize(&text, &lang, &options).await;
+        
+        let mut state = app_state_clone.borrow_mut();
+        match result {
+            Ok(audio) => {
+                let player = gtk::MediaFile::for_filename(audio.path());
+                player.play();
+                state.tts_player = Some((player, audio));
+                state.status_bar.push(0, &gettext("Speaking..."));
+            },
+            Err(error) => {
+                state.status_bar.push(0, &gettext_f("Speech failed: {}", &[&error.to_string()]));
+            }
+        }
+    });
+}
+
+/// Transcribe an audio file with whisper.cpp and translate the transcript from
+/// the language whisper.cpp recognized. A recording's temporary file is
+/// deleted once the transcription is done.
+fn transcribe_and_translate<P: AsRef<Path> + 'static>(audio: P, app_state: &Rc<RefCell<AppState>>) {
+    let options = {
+        let state = app_state.borrow();
+        state.status_bar.push(0, &gettext("Transcribing speech..."));
+        state.settings.whisper_options()
+    };
+    
+    let app_state_clone = app_state.clone();
+    spawn_local_task(move || async move {
+        match dictation::transcribe(audio.as_ref(), &options).await {
+            Ok(transcript) => {
+                app_state_clone.borrow().input_buffer.set_text(&transcript.text);
+                perform_translation(transcript.text, TranslationOrigin::Speech, transcript.language.as_deref(), &app_state_clone);
+            },
+            Err(error) => {
+                app_state_clone.borrow().status_bar.push(0, &gettext_f("Transcription failed: {}", &[&error.to_string()]));
+            }
+        }
+    });
+}
+
+/// Create a button that reads text aloud
+fn create_listen_button(tooltip: &str) -> Button {
+    let button = Button::from_icon_name("audio-volume-high-symbolic");
+    button.set_tooltip_text(Some(tooltip));
+    button
+}
+
+/// Update a transliteration label, keeping it hidden when there is nothing to show
+fn set_transliteration(label: &Label, transliteration: Option<&str>, show: bool) {
+    label.set_text(transliteration.unwrap_or_default());
+    label.set_visible(show && transliteration.is_some());
+}
+
+/// Create a label for the romanized reading shown under a text view
+fn create_transliteration_label() -> Label {
+    let label = Label::new(None);
+    label.set_halign(gtk::Align::Start);
+    label.set_wrap(true);
+    label.set_selectable(true);
+    label.add_css_class("transliteration-label");
+    label.set_visible(false);
+    label
+}
+
+/// Swap the source and target languages together with the input and output text
+fn swap_languages(app_state: &Rc<RefCell<AppState>>) {
+    let state = app_state.borrow();
+    
+    let source_lang = state.source_lang.active_id().map(|id| id.to_string()).unwrap_or_default();
+    let target_lang = state.target_lang.active_id().map(|id| id.to_string()).unwrap_or_default();
+    let input = state.input_buffer.text(&state.input_buffer.start_iter(), &state.input_buffer.end_iter(), false).to_string();
+    let output = state.output_buffer.text(&state.output_buffer.start_iter(), &state.output_buffer.end_iter(), false).to_string();
+    
+    // "Detect language" cannot be a target, so use the detected language or
+    // the configured counterpart of the current target
+    let new_target = match detection::resolve_auto(&input, &source_lang) {
+        languages::AUTO if target_lang == state.settings.primary_language => state.settings.secondary_language.clone(),
+        languages::AUTO => state.settings.primary_language.clone(),
+        source => source.to_string(),
+    };
+    
+    state.source_lang.set_active_id(Some(&target_lang));
+    state.target_lang.set_active_id(Some(&new_target));
+    
+    // Only a finished translation moves to the input, not progress or errors
+    let shows_translation = !output.is_empty() && state.output_message.as_deref() != Some(output.as_str());
+    if shows_translation {
+        state.input_buffer.set_text(&output);
+        state.output_buffer.set_text(&input);
+    }
+    
+    state.status_bar.push(0, &gettext_f(
+        "Swapped languages: {} → {}",
+        &[&languages::display_name(&target_lang), &languages::display_name(&new_target)]
+    ));
+}
+
+/// Show the locally detected language of the input while the source
+/// language is set to "Detect language", and lay the input out in the
+/// direction of the selected or detected language
+fn update_detected_language(label: &Label, input_view: &TextView, source_lang: &ComboBoxText) {
+    let input_buffer = input_view.buffer();
+    let source = source_lang.active_id().map(|id| id.to_string()).unwrap_or_default();
+    let is_auto = source == languages::AUTO;
+    let text = input_buffer.text(&input_buffer.start_iter(), &input_buffer.end_iter(), false);
+    
+    input_view.set_direction(text_direction(detection::resolve_auto(&text, &source)));
+    
+    match detection::detect(&text).filter(|_| is_auto) {
+        Some(detection) => {
+            let label_text = if detection.is_confident() {
+                gettext_f("Detected: {}", &[&detection.summary()])
+            } else {
+                gettext_f("Possibly: {}", &[&detection.summary()])
+            };
+            label.set_text(&label_text);
+            label.set_visible(true);
+        }
+        None => label.set_visible(false),
+    }
+}
+
+/// Ask for the passphrase protecting the encrypted API key file.
+///
+/// Used when no Secret Service keyring is available. If the file does not exist
+/// yet, the passphrase is chosen here and the plaintext keys are moved into it.
+fn show_passphrase_dialog(parent: &ApplicationWindow, app_state: &Rc<RefCell<AppState>>) {
+    let creating = !secrets::encrypted_file_exists();
+    
+    let dialog = gtk::Dialog::new();
+    dialog.set_title(Some(if creating { &gettext("Protect API Keys") } else { &gettext("Unlock API Keys") }));
+    dialog.set_modal(true);
+    dialog.set_transient_for(Some(parent));
+    dialog.set_default_width(380);
+    
+    let content_area = dialog.content_area();
+    content_area.set_margin_start(16);
+    content_area.set_margin_end(16);
+    content_area.set_margin_top(16);
+    content_area.set_margin_bottom(16);
+    content_area.set_spacing(12);
+    
+    let message = Label::new(Some(&if creating {
+        gettext("No system keyring is available. Choose a passphrase to encrypt your API keys instead of storing them in plain text.")
+    } else {
+        gettext("Enter the passphrase to unlock your stored API keys.")
+    }));
+    message.set_wrap(true);
+    message.set_max_width_chars(50);
+    message.set_halign(gtk::Align::Start);
+    
+    let passphrase_entry = gtk::PasswordEntry::new();
+    passphrase_entry.set_show_peek_icon(true);
+    passphrase_entry.set_activates_default(true);
+    
+    let confirm_entry = gtk::PasswordEntry::new();
+    confirm_entry.set_placeholder_text(Some(&gettext("Repeat passphrase")));
+    confirm_entry.set_activates_default(true);
+    confirm_entry.set_visible(creating);
+    
+    let error_label = Label::new(None);
+    error_label.add_css_class("error");
+    error_label.set_halign(gtk::Align::Start);
+    error_label.set_visible(false);
+    
+    content_area.append(&message);
+    content_area.append(&passphrase_entry);
+    content_area.append(&confirm_entry);
+    content_area.append(&error_label);
+    
+    dialog.add_button(&if creating { gettext("Not Now") } else { gettext("Skip") }, ResponseType::Cancel);
+    dialog.add_button(&if creating { gettext("Encrypt") } else { gettext("Unlock") }, ResponseType::Ok);
+    dialog.set_default_response(ResponseType::Ok);
+    
+    let app_state = app_state.clone();
+    dialog.connect_response(move |dialog, response| {
+        if response != ResponseType::Ok {
+            dialog.close();
+            return;
+        }
+        
+        let passphrase = passphrase_entry.text().to_string();
+        let show_error = |text: &str| {
+            error_label.set_text(text);
+            error_label.set_visible(true);
+        };
+        
+        if passphrase.is_empty() {
+            show_error(&gettext("Please enter a passphrase"));
+            return;
+        }
+        if creating && confirm_entry.text() != passphrase {
+            show_error(&gettext("The passphrases do not match"));
+            return;
+        }
+        
+        secrets::set_store(Some(SecretStore::EncryptedFile { passphrase }));
+        
+        let mut state = app_state.borrow_mut();
+        let mut settings = state.settings.clone();
+        match settings.load_secrets() {
+            Ok(()) => {
+                state.translation_manager.apply_settings(&settings);
+                state.settings = settings;
+                state.status_bar.push(0, &gettext("API keys unlocked"));
+                dialog.close();
+                
+                drop(state);
+                poll_deepl_usage(&app_state);
+            },
+            Err(e) => {
+                // Keep keys out of reach of a wrong passphrase
+                secrets::set_store(None);
+                passphrase_entry.set_text("");
+                show_error(&e);
+            }
+        }
+    });
+    
+    dialog.show();
+}
+
 /// Builds the GTK user interface, sets up translation logic, and attaches the hotkey receiver.
 fn build_ui(app: &Application) {
     // Create window manager
@@ -148,8 +581,15 @@ fn build_ui(app: &Application) {
     // Try to install tools if needed
     window_manager.install_tools_if_needed();
     
-    // Load settings
-    let settings = Settings::load();
+    // Load settings, with API keys from the system keyring if one is running
+    let mut settings = Settings::load();
+    if let Some(keyring) = secrets::open_keyring() {
+        secrets::set_store(Some(keyring));
+        if let Err(e) = settings.load_secrets() {
+            eprintln!("Could not load API keys from the keyring: {}", e);
+            secrets::set_store(None);
+        }
+    }
     
     // Initialize database
     let db = match Database::new() {
@@ -161,11 +601,15 @@ fn build_ui(app: &Application) {
         }
     };
     
+    // Prune the history in the background with the configured limits
+    let retention = Arc::new(Mutex::new(settings.retention_policy()));
+    Database::spawn_prune_job(retention.clone());
+    
     // Initialize translation manager
     let mut translation_manager = TranslationManager::new();
     
-    // Set active service from settings
-    translation_manager.set_active_service(settings.active_service.clone());
+    // Set active service, pivot language and service configurations from settings
+    translation_manager.apply_settings(&settings);
     
     // Create the main window with a header bar
     let window = ApplicationWindow::builder()
@@ -174,7 +618,7 @@ fn build_ui(app: &Application) {
         .default_width(settings.window_width)
         .default_height(settings.window_height)
         .build();
-        
+    
     // Set up window properties for better desktop integration
     window_manager.setup_window(&window);
     
@@ -206,12 +650,12 @@ fn build_ui(app: &Application) {
     let menu_model = gio::Menu::new();
     
     // Settings menu item
-    let settings_menu = gio::MenuItem::new(Some("Settings"), None);
+    let settings_menu = gio::MenuItem::new(Some(&gettext("Settings")), None);
     settings_menu.set_detailed_action("app.settings");
     menu_model.append_item(&settings_menu);
     
     // About menu item
-    let about_menu = gio::MenuItem::new(Some("About"), None);
+    let about_menu = gio::MenuItem::new(Some(&gettext("About")), None);
     about_menu.set_detailed_action("app.about");
     menu_model.append_item(&about_menu);
     
@@ -235,7 +679,7 @@ fn build_ui(app: &Application) {
         about_dialog.set_modal(true);
         about_dialog.set_program_name(Some("SwiftLingo"));
         about_dialog.set_version(Some(env!("CARGO_PKG_VERSION")));
-        about_dialog.set_comments(Some("A fast and convenient translator app"));
+        about_dialog.set_comments(Some(&gettext("A fast and convenient translator app")));
         about_dialog.set_authors(&["Anassk"]);
         about_dialog.set_license_type(gtk::License::Gpl30);
         about_dialog.present();
@@ -255,14 +699,14 @@ fn build_ui(app: &Application) {
     info_box.set_margin_top(16);
     info_box.set_margin_bottom(8);
     
-    let hotkey_info = Label::new(Some("Select text anywhere and press Ctrl+Alt+T"));
+    let hotkey_info = Label::new(Some(&gettext("Select text anywhere and press Ctrl+Alt+T")));
     hotkey_info.add_css_class("info-label");
     info_box.append(&hotkey_info);
     
     main_tab_content.append(&info_box);
     
     // Language selection area
-    let lang_frame = Frame::new(Some("Language Options"));
+    let lang_frame = Frame::new(Some(&gettext("Language Options")));
     lang_frame.set_margin_start(16);
     lang_frame.set_margin_end(16);
     lang_frame.set_margin_bottom(16);
@@ -273,36 +717,39 @@ fn build_ui(app: &Application) {
     lang_box.set_margin_top(16);
     lang_box.set_margin_bottom(16);
     
-    let source_lang_label = Label::new(Some("From:"));
+    let source_lang_label = Label::new(Some(&gettext("From:")));
     let source_lang = ComboBoxText::new();
     source_lang.add_css_class("language-selector");
-    source_lang.append(Some("auto"), "Detect language");
-    for (code, name) in LANGUAGES.iter() {
-        if *code != "auto" {
-            source_lang.append(Some(code), name);
-        }
-    }
+    append_languages(&source_lang, true);
     
     // Set default source language from settings
     source_lang.set_active_id(Some(&settings.default_source_lang));
     
-    let target_lang_label = Label::new(Some("To:"));
+    let target_lang_label = Label::new(Some(&gettext("To:")));
     let target_lang = ComboBoxText::new();
     target_lang.add_css_class("language-selector");
-    for (code, name) in LANGUAGES.iter() {
-        if *code != "auto" {
-            target_lang.append(Some(code), name);
-        }
-    }
+    append_languages(&target_lang, false);
     
     // Set default target language from settings
     target_lang.set_active_id(Some(&settings.default_target_lang));
     
+    let swap_button = Button::from_icon_name("object-flip-horizontal-symbolic");
+    swap_button.set_tooltip_text(Some(&gettext("Swap languages (Ctrl+Shift+S)")));
+    
     lang_box.append(&source_lang_label);
     lang_box.append(&source_lang);
+    lang_box.append(&swap_button);
     lang_box.append(&target_lang_label);
     lang_box.append(&target_lang);
     
+    // Toggle for the romanized readings under the text views
+    let transliteration_toggle = ToggleButton::with_label(&gettext("Romanization"));
+    transliteration_toggle.set_tooltip_text(Some(&gettext("Show the romanized reading of the source and translation")));
+    transliteration_toggle.set_active(settings.show_transliteration);
+    transliteration_toggle.set_hexpand(true);
+    transliteration_toggle.set_halign(gtk::Align::End);
+    lang_box.append(&transliteration_toggle);
+    
     lang_frame.set_child(Some(&lang_box));
     main_tab_content.append(&lang_frame);
     
@@ -319,7 +766,7 @@ fn build_ui(app: &Application) {
     left_box.set_hexpand(true);
     left_box.set_vexpand(true);
     
-    let input_frame = Frame::new(Some("Text to Translate"));
+    let input_frame = Frame::new(Some(&gettext("Text to Translate")));
     input_frame.set_vexpand(true);
     input_frame.set_hexpand(true);
     
@@ -334,18 +781,39 @@ fn build_ui(app: &Application) {
     input_text.set_vexpand(true);
     input_text.set_hexpand(true);
     input_scroll.set_child(Some(&input_text));
+    
+    // Accept dropped audio files for transcription
+    let audio_drop_target = gtk::DropTarget::new(gio::File::static_type(), gtk::gdk::DragAction::COPY);
+    input_text.add_controller(audio_drop_target.clone());
     input_frame.set_child(Some(&input_scroll));
     
     left_box.append(&input_frame);
     
+    let source_transliteration_label = create_transliteration_label();
+    left_box.append(&source_transliteration_label);
+    
+    // Local guess at the source language while "Detect language" is selected
+    let detected_label = Label::new(None);
+    detected_label.set_halign(gtk::Align::Start);
+    detected_label.add_css_class("dim-label");
+    detected_label.add_css_class("detected-language");
+    detected_label.set_visible(false);
+    left_box.append(&detected_label);
+    
     // Button to get selection in the left box
     let button_box = GtkBox::new(Orientation::Horizontal, 8);
     button_box.set_halign(gtk::Align::End);
     button_box.set_margin_top(8);
     
-    let get_selection_button = create_button("Get Selection", true, false);
-    let translate_button = create_button("Translate", true, false);
+    let listen_input_button = create_listen_button(&gettext("Listen to the text"));
+    let microphone_button = ToggleButton::new();
+    microphone_button.set_icon_name("audio-input-microphone-symbolic");
+    microphone_button.set_tooltip_text(Some(&gettext("Dictate text (click again to stop)")));
+    let get_selection_button = create_button(&gettext("Get Selection"), true, false);
+    let translate_button = create_button(&gettext("Translate"), true, false);
     
+    button_box.append(&listen_input_button);
+    button_box.append(&microphone_button);
     button_box.append(&get_selection_button);
     button_box.append(&translate_button);
     
@@ -356,7 +824,7 @@ fn build_ui(app: &Application) {
     right_box.set_hexpand(true);
     right_box.set_vexpand(true);
     
-    let output_frame = Frame::new(Some("Translation"));
+    let output_frame = Frame::new(Some(&gettext("Translation")));
     output_frame.set_vexpand(true);
     output_frame.set_hexpand(true);
     
@@ -377,14 +845,59 @@ fn build_ui(app: &Application) {
     
     right_box.append(&output_frame);
     
+    let transliteration_label = create_transliteration_label();
+    right_box.append(&transliteration_label);
+    
+    // Intermediate text shown when a translation went through a pivot language
+    let pivot_label = Label::new(None);
+    pivot_label.set_halign(gtk::Align::Start);
+    pivot_label.set_wrap(true);
+    pivot_label.set_selectable(true);
+    pivot_label.add_css_class("dim-label");
+    pivot_label.add_css_class("pivot-label");
+    pivot_label.set_visible(false);
+    
+    right_box.append(&pivot_label);
+    
+    // Service that produced the translation and the routing rule that picked it
+    let service_label = Label::new(None);
+    service_label.set_halign(gtk::Align::Start);
+    service_label.set_wrap(true);
+    service_label.add_css_class("dim-label");
+    service_label.add_css_class("service-label");
+    service_label.set_visible(false);
+    
+    right_box.append(&service_label);
+    
+    // Expandable dictionary panel for single-word lookups
+    let dictionary_expander = Expander::new(Some(&gettext("Dictionary")));
+    dictionary_expander.add_css_class("dictionary-panel");
+    dictionary_expander.set_visible(false);
+    
+    let dictionary_scroll = ScrolledWindow::new();
+    dictionary_scroll.set_propagate_natural_height(true);
+    dictionary_scroll.set_max_content_height(200);
+    
+    let dictionary_label = Label::new(None);
+    dictionary_label.set_halign(gtk::Align::Start);
+    dictionary_label.set_valign(gtk::Align::Start);
+    dictionary_label.set_wrap(true);
+    dictionary_label.set_selectable(true);
+    dictionary_scroll.set_child(Some(&dictionary_label));
+    dictionary_expander.set_child(Some(&dictionary_scroll));
+    
+    right_box.append(&dictionary_expander);
+    
     // Add save and copy button for the translation
     let action_box = GtkBox::new(Orientation::Horizontal, 8);
     action_box.set_halign(gtk::Align::End);
     action_box.set_margin_top(8);
     
-    let save_button = Button::with_label("Save to List");
-    let copy_button = create_button("Copy Translation", true, false);
+    let listen_output_button = create_listen_button(&gettext("Listen to the translation"));
+    let save_button = Button::with_label(&gettext("Save to List"));
+    let copy_button = create_button(&gettext("Copy Translation"), true, false);
     
+    action_box.append(&listen_output_button);
     action_box.append(&save_button);
     action_box.append(&copy_button);
     
@@ -398,7 +911,7 @@ fn build_ui(app: &Application) {
     
     // Status bar at the bottom
     let status_bar = Statusbar::new();
-    status_bar.push(0, "Ready");
+    status_bar.push(0, &gettext("Ready"));
     main_tab_content.append(&status_bar);
     
     // Create history panel with input/output buffer references
@@ -408,6 +921,7 @@ fn build_ui(app: &Application) {
         Rc::new(RefCell::new(output_buffer.clone())),
         Rc::new(RefCell::new(source_lang.clone())),
         Rc::new(RefCell::new(target_lang.clone())),
+        settings.history_view_limit(),
     );
     
     // Create AppState to hold shared widgets and references
@@ -416,17 +930,27 @@ fn build_ui(app: &Application) {
         db,
         input_buffer: input_buffer.clone(),
         output_buffer: output_buffer.clone(),
+        output_message: None,
+        source_transliteration_label: source_transliteration_label.clone(),
+        transliteration_label: transliteration_label.clone(),
+        pivot_label: pivot_label.clone(),
+        service_label: service_label.clone(),
+        dictionary_expander: dictionary_expander.clone(),
+        dictionary_label: dictionary_label.clone(),
         source_lang: source_lang.clone(),
         target_lang: target_lang.clone(),
         status_bar: status_bar.clone(),
         history_panel: history_panel.clone(),
         settings,
         window_manager,
+        tts_player: None,
+        recording: None,
+        retention,
     }));
     
     // Add tabs to notebook
-    main_notebook.append_page(&main_tab_content, Some(&Label::new(Some("Translate"))));
-    main_notebook.append_page(history_panel.get_widget(), Some(&Label::new(Some("History"))));
+    main_notebook.append_page(&main_tab_content, Some(&Label::new(Some(&gettext("Translate")))));
+    main_notebook.append_page(history_panel.get_widget(), Some(&Label::new(Some(&gettext("History")))));
     
     window.set_child(Some(&main_notebook));
     
@@ -434,15 +958,48 @@ fn build_ui(app: &Application) {
     let window_clone = window.clone();
     let app_state_clone = app_state.clone();
     settings_action.connect_activate(move |_, _| {
-        let settings_rc = Rc::new(RefCell::new(app_state_clone.borrow().settings.clone()));
-        let settings_dialog = SettingsDialog::new(&window_clone, settings_rc.clone());
+        let (settings_rc, db, health) = {
+            let state = app_state_clone.borrow();
+            (
+                Rc::new(RefCell::new(state.settings.clone())),
+                state.db.clone(),
+                state.translation_manager.health().clone(),
+            )
+        };
+        let settings_dialog = SettingsDialog::new(&window_clone, settings_rc.clone(), db, health);
         
-        // Connect to dialog response to update history when settings are applied
-        let history_panel_clone = app_state_clone.borrow().history_panel.clone();
+        // Connect to dialog response to apply saved settings and update history
+        let app_state_inner = app_state_clone.clone();
+        let window_inner = window_clone.clone();
         settings_dialog.connect_response(move |response| {
             if response == ResponseType::Ok || response == ResponseType::Apply {
+                let new_settings = settings_rc.borrow().clone();
+                let history_panel = {
+                    let mut state = app_state_inner.borrow_mut();
+                    state.translation_manager.apply_settings(&new_settings);
+                    
+                    // Apply changed history limits right away
+                    let policy = new_settings.retention_policy();
+                    *state.retention.lock().unwrap() = policy;
+                    if let Err(e) = state.db.prune(&policy) {
+                        eprintln!("Failed to prune history: {}", e);
+                    }
+                    state.history_panel.set_view_limit(new_settings.history_view_limit());
+                    
+                    state.settings = new_settings;
+                    state.history_panel.clone()
+                };
+                
                 // Update history panel to reflect any changes in translation settings
-                history_panel_clone.update_view();
+                history_panel.update_view();
+                
+                // A DeepL key may have been added or changed
+                poll_deepl_usage(&app_state_inner);
+                
+                // Offer to encrypt newly entered keys when no keyring is available
+                if !secrets::is_unlocked() && Settings::has_plaintext_keys() {
+                    show_passphrase_dialog(&window_inner, &app_state_inner);
+                }
             }
         });
         
@@ -463,7 +1020,7 @@ fn build_ui(app: &Application) {
             println!("Ctrl+Alt+T pressed within app");
             
             // Update status
-            app_state_clone.borrow().status_bar.push(0, "Getting selection...");
+            app_state_clone.borrow().status_bar.push(0, &gettext("Getting selection..."));
             
             let selection = get_selected_text();
             if !selection.is_empty() {
@@ -474,16 +1031,66 @@ fn build_ui(app: &Application) {
                 app_state_clone.borrow().window_manager.focus_window(&window_clone);
                 
                 // Trigger translation
-                perform_translation(selection, &app_state_clone);
+                perform_translation(selection, TranslationOrigin::Hotkey, None, &app_state_clone);
             } else {
-                app_state_clone.borrow().status_bar.push(0, "No text selected");
+                app_state_clone.borrow().status_bar.push(0, &gettext("No text selected"));
             }
             return Inhibit(true);
         }
+        
+        // Check for Ctrl+Shift+S
+        if key == gtk::gdk::Key::S &&
+           state.contains(gtk::gdk::ModifierType::CONTROL_MASK) &&
+           state.contains(gtk::gdk::ModifierType::SHIFT_MASK) {
+            swap_languages(&app_state_clone);
+            return Inhibit(true);
+        }
         Inhibit(false)
     });
     window.add_controller(key_controller);
     
+    // Connect swap button
+    let app_state_clone = app_state.clone();
+    swap_button.connect_clicked(move |_| {
+        swap_languages(&app_state_clone);
+    });
+    
+    // Update the detected language and text direction as the input or
+    // source language changes
+    let label = detected_label.clone();
+    let view = input_text.clone();
+    let combo = source_lang.clone();
+    input_buffer.connect_changed(move |_| {
+        update_detected_language(&label, &view, &combo);
+    });
+    
+    let label = detected_label.clone();
+    let view = input_text.clone();
+    source_lang.connect_changed(move |combo| {
+        update_detected_language(&label, &view, combo);
+    });
+    update_detected_language(&detected_label, &input_text, &source_lang);
+    
+    // Lay the translation out in the direction of the target language
+    let view = output_text.clone();
+    target_lang.connect_changed(move |combo| {
+        let target = combo.active_id().map(|id| id.to_string()).unwrap_or_default();
+        view.set_direction(text_direction(&target));
+    });
+    output_text.set_direction(text_direction(&target_lang.active_id().map(|id| id.to_string()).unwrap_or_default()));
+    
+    // Connect romanization toggle
+    let app_state_clone = app_state.clone();
+    transliteration_toggle.connect_toggled(move |toggle| {
+        let mut state = app_state_clone.borrow_mut();
+        state.settings.show_transliteration = toggle.is_active();
+        state.settings.save();
+        
+        for label in [&state.source_transliteration_label, &state.transliteration_label] {
+            label.set_visible(toggle.is_active() && !label.text().is_empty());
+        }
+    });
+    
     // Connect translate button signal
     let app_state_clone = app_state.clone();
     translate_button.connect_clicked(move |_| {
@@ -496,28 +1103,112 @@ fn build_ui(app: &Application) {
             ).to_string()
         };
         
-        perform_translation(text, &app_state_clone);
+        perform_translation(text, TranslationOrigin::Typed, None, &app_state_clone);
+    });
+    
+    // Connect listen buttons
+    let app_state_clone = app_state.clone();
+    listen_input_button.connect_clicked(move |_| {
+        let (text, lang) = {
+            let state = app_state_clone.borrow();
+            let text = state.input_buffer.text(
+                &state.input_buffer.start_iter(),
+                &state.input_buffer.end_iter(),
+                false
+            ).to_string();
+            let lang = state.source_lang.active_id()
+                .unwrap_or_else(|| gtk::glib::GString::from("auto"))
+                .to_string();
+            (text, lang)
+        };
+        
+        speak_text(text, lang, &app_state_clone);
+    });
+    
+    let app_state_clone = app_state.clone();
+    listen_output_button.connect_clicked(move |_| {
+        let (text, lang) = {
+            let state = app_state_clone.borrow();
+            let text = state.output_buffer.text(
+                &state.output_buffer.start_iter(),
+                &state.output_buffer.end_iter(),
+                false
+            ).to_string();
+            let lang = state.target_lang.active_id()
+                .unwrap_or_else(|| gtk::glib::GString::from("es"))
+                .to_string();
+            (text, lang)
+        };
+        
+        speak_text(text, lang, &app_state_clone);
+    });
+    
+    // Connect microphone button: first click starts recording, second click transcribes
+    let app_state_clone = app_state.clone();
+    microphone_button.connect_toggled(move |button| {
+        if button.is_active() {
+            let started = Recording::start();
+            let started_ok = started.is_ok();
+            {
+                let mut state = app_state_clone.borrow_mut();
+                match started {
+                    Ok(recording) => {
+                        state.recording = Some(recording);
+                        state.status_bar.push(0, &gettext("Recording... click the microphone again to stop"));
+                    },
+                    Err(error) => {
+                        state.status_bar.push(0, &error);
+                    }
+                }
+            }
+            
+            // Reset the button outside the borrow, as this re-enters the handler
+            if !started_ok {
+                button.set_active(false);
+            }
+        } else {
+            let recording = app_state_clone.borrow_mut().recording.take();
+            if let Some(recording) = recording {
+                match recording.stop() {
+                    Ok(audio) => transcribe_and_translate(audio, &app_state_clone),
+                    Err(error) => app_state_clone.borrow().status_bar.push(0, &error),
+                }
+            }
+        }
+    });
+    
+    // Connect audio file drops on the input view
+    let app_state_clone = app_state.clone();
+    audio_drop_target.connect_drop(move |_, value, _, _| {
+        let path = value.get::<gio::File>().ok().and_then(|file| file.path());
+        match path {
+            Some(path) if dictation::is_audio_file(&path) => {
+                transcribe_and_translate(path, &app_state_clone);
+                true
+            },
+            _ => false,
+        }
     });
     
     // Connect get selection button
     let app_state_clone = app_state.clone();
     get_selection_button.connect_clicked(move |_| {
-        app_state_clone.borrow().status_bar.push(0, "Getting selection...");
+        app_state_clone.borrow().status_bar.push(0, &gettext("Getting selection..."));
         let selection = get_selected_text();
         println!("Got selection: {}", selection);
         
         if !selection.is_empty() && selection != "Failed to get X11 selection" && selection != "Failed to get Wayland selection" {
             app_state_clone.borrow().input_buffer.set_text(&selection);
-            perform_translation(selection, &app_state_clone);
+            perform_translation(selection, TranslationOrigin::Clipboard, None, &app_state_clone);
         } else {
-            app_state_clone.borrow().status_bar.push(0, "No text selected");
+            app_state_clone.borrow().status_bar.push(0, &gettext("No text selected"));
         }
     });
     
     // Connect save button
     let app_state_clone = app_state.clone();
     save_button.connect_clicked(move |button| {
-        let (input_text, output_text, source_lang, target_lang, status_bar, db) = {
+        let (input_text, output_text, source_lang, target_lang, status_bar, db, history_paused) = {
             let state = app_state_clone.borrow();
             let input_text = state.input_buffer.text(
                 &state.input_buffer.start_iter(),
@@ -538,34 +1229,30 @@ fn build_ui(app: &Application) {
             let target_lang = state.target_lang.active_id()
                 .unwrap_or_else(|| gtk::glib::GString::from("es"))
                 .to_string();
-                
+            
             (
                 input_text,
                 output_text,
                 source_lang,
                 target_lang,
                 state.status_bar.clone(),
-                state.db.clone()
+                state.db.clone(),
+                !state.settings.auto_save_history
             )
         };
         
-        if input_text.is_empty() || output_text.is_empty() || output_text == "Translating..." {
-            status_bar.push(0, "No translation to save");
+        if input_text.is_empty() || output_text.is_empty() || output_text == gettext("Translating...") {
+            status_bar.push(0, &gettext("No translation to save"));
             return;
         }
         
-        // First add to database history
-        let translation_id = match db.add_translation(
-            &input_text, &source_lang, &output_text, &target_lang
-        ) {
-            Ok(id) => id,
-            Err(_) => {
-                status_bar.push(0, "Failed to save translation");
-                return;
-            }
-        };
+        // Translations in lists are kept in the history too
+        if history_paused {
+            status_bar.push(0, &gettext("History is paused, resume it in Settings to save translations"));
+            return;
+        }
         
-        // Now show dialog to select a list
+        // Show dialog to select a list
         let popover = Popover::new();
         popover.set_parent(button);
         
@@ -575,12 +1262,12 @@ fn build_ui(app: &Application) {
         dialog_box.set_margin_top(10);
         dialog_box.set_margin_bottom(10);
         
-        let title = Label::new(Some("Save to List"));
+        let title = Label::new(Some(&gettext("Save to List")));
         title.add_css_class("title-4");
         
         let list_combo = ComboBoxText::new();
-        list_combo.append(None, "Select a list");
-        list_combo.append(Some("new"), "Create New List...");
+        list_combo.append(None, &gettext("Select a list"));
+        list_combo.append(Some("new"), &gettext("Create New List..."));
         
         // Add lists from the database
         if let Ok(lists) = db.get_lists() {
@@ -596,7 +1283,7 @@ fn build_ui(app: &Application) {
         new_list_box.set_visible(false);
         
         let new_list_entry = gtk::Entry::new();
-        new_list_entry.set_placeholder_text(Some("Enter list name"));
+        new_list_entry.set_placeholder_text(Some(&gettext("Enter list name")));
         new_list_entry.set_hexpand(true);
         
         new_list_box.append(&new_list_entry);
@@ -605,8 +1292,8 @@ fn build_ui(app: &Application) {
         let button_box = GtkBox::new(Orientation::Horizontal, 5);
         button_box.set_halign(gtk::Align::End);
         
-        let cancel_button = Button::with_label("Cancel");
-        let save_list_button = Button::with_label("Save");
+        let cancel_button = Button::with_label(&gettext("Cancel"));
+        let save_list_button = Button::with_label(&gettext("Save"));
         save_list_button.add_css_class("suggested-action");
         
         button_box.append(&cancel_button);
@@ -642,35 +1329,48 @@ fn build_ui(app: &Application) {
         let status_bar_clone = status_bar.clone();
         
         save_list_button.connect_clicked(move |_| {
-            if let Some(id) = list_combo.active_id() {
-                if id == "new" {
-                    // Create new list
+            // Pick or create the list first, so nothing is saved until one is chosen
+            let (list_id, saved_message) = match list_combo.active_id() {
+                Some(id) if id == "new" => {
                     let list_name = new_list_entry.text().to_string();
                     if list_name.is_empty() {
-                        status_bar_clone.push(0, "Please enter a list name");
+                        status_bar_clone.push(0, &gettext("Please enter a list name"));
                         return;
                     }
                     
                     match db.create_list(&list_name) {
-                        Ok(list_id) => {
-                            let _ = db.add_to_list(list_id, translation_id);
-                            status_bar_clone.push(0, "Translation saved to new list");
-                        },
+                        Ok(list_id) => (list_id, gettext("Translation saved to new list")),
                         Err(_) => {
-                            status_bar_clone.push(0, "Failed to create list");
+                            status_bar_clone.push(0, &gettext("Failed to create list"));
+                            return;
                         }
                     }
-                } else {
-                    // Add to existing list
-                    if let Ok(list_id) = id.to_string().parse::<i64>() {
-                        let _ = db.add_to_list(list_id, translation_id);
-                        status_bar_clone.push(0, "Translation saved to list");
-                    }
+                },
+                Some(id) => match id.parse::<i64>() {
+                    Ok(list_id) => (list_id, gettext("Translation saved to list")),
+                    Err(_) => return,
+                },
+                None => {
+                    status_bar_clone.push(0, &gettext("Please select a list"));
+                    return;
                 }
-            } else {
-                status_bar_clone.push(0, "Please select a list");
-                return;
-            }
+            };
+            
+            // Find the translation in the history, adding it if needed, without
+            // counting the save as another use
+            let metadata = TranslationMetadata {
+                service: None,
+                detected_lang: None,
+                latency_ms: None,
+                origin: TranslationOrigin::Typed,
+            };
+            let saved = db.find_or_add_translation(&input_text, &source_lang, &output_text, &target_lang, &metadata)
+                .and_then(|translation_id| db.add_to_list(list_id, translation_id));
+            
+            match saved {
+                Ok(_) => status_bar_clone.push(0, &saved_message),
+                Err(_) => status_bar_clone.push(0, &gettext("Failed to save translation")),
+            };
             
             popover_ref.popdown();
         });
@@ -692,19 +1392,19 @@ fn build_ui(app: &Application) {
             (text, state.status_bar.clone())
         };
         
-        if text.is_empty() || text == "Translating..." {
-            status_bar.push(0, "No translation to copy");
+        if text.is_empty() || text == gettext("Translating...") {
+            status_bar.push(0, &gettext("No translation to copy"));
             return;
         }
         
         // Use our native clipboard implementation
         if selection::set_clipboard_text(&text) {
-            status_bar.push(0, "Translation copied to clipboard");
+            status_bar.push(0, &gettext("Translation copied to clipboard"));
         } else {
-            status_bar.push(0, "Failed to copy to clipboard");
+            status_bar.push(0, &gettext("Failed to copy to clipboard"));
         }
     });
-
+    
     // Set up monitoring for the global hotkey trigger file
     let home_dir = env::var("HOME").unwrap_or_else(|_| ".".to_string());
     let selection_path = format!("{}/.config/translator-app/selection.txt", home_dir);
@@ -751,9 +1451,9 @@ fn build_ui(app: &Application) {
                             app_state_clone.borrow().window_manager.focus_window(&window_clone);
                             
                             // Trigger translation
-                            perform_translation(selection, &app_state_clone);
+                            perform_translation(selection, TranslationOrigin::Hotkey, None, &app_state_clone);
                         } else {
-                            app_state_clone.borrow().status_bar.push(0, "No text selected");
+                            app_state_clone.borrow().status_bar.push(0, &gettext("No text selected"));
                         }
                     }
                 }
@@ -795,6 +1495,19 @@ fn build_ui(app: &Application) {
     
     // Show everything
     window.present();
+    
+    // Keep DeepL's reported usage current while a key is configured
+    poll_deepl_usage(&app_state);
+    let app_state_clone = app_state.clone();
+    glib::source::timeout_add_local(DEEPL_USAGE_POLL_INTERVAL, move || {
+        poll_deepl_usage(&app_state_clone);
+        Continue(true)
+    });
+    
+    // Without a keyring, API keys live in an encrypted file or still in plain text
+    if !secrets::is_unlocked() && (secrets::encrypted_file_exists() || Settings::has_plaintext_keys()) {
+        show_passphrase_dialog(&window, &app_state);
+    }
 }
 
 /// Apply theme based on dark mode setting
@@ -814,7 +1527,7 @@ fn apply_theme(dark_mode: bool) {
             );
         }
     }
-
+    
     // Apply dark mode if enabled
     if let Some(settings) = gtk::Settings::default() {
         settings.set_property("gtk-application-prefer-dark-theme", dark_mode);
@@ -851,6 +1564,9 @@ fn get_css_path() -> Option<String> {
 }
 
 fn main() {
+    // Translate the interface before any widget is created
+    i18n::init(Settings::load().ui_language.as_deref());
+    
     // Create the window manager to check for window management tools
     let window_manager = WindowManager::new();
     let has_window_tools = window_manager.check_clipboard_tools();
@@ -864,7 +1580,7 @@ fn main() {
         .application_id(APP_ID)
         .flags(gio::ApplicationFlags::ALLOW_REPLACEMENT | gio::ApplicationFlags::REPLACE)
         .build();
-
+    
     // Create a Tokio runtime for async tasks.
     let rt = Runtime::new().expect("Unable to create Runtime");
     let _enter = rt.enter();
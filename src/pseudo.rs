@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use reqwest::StatusCode;
+use crate::translation::TranslationError;
+
+/// State of the generator deciding which requests fail
+static RANDOM_STATE: AtomicU64 = AtomicU64::new(0);
+
+/// What the offline backend returns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PseudoMode {
+    /// Accented, padded text between bracket markers
+    Pseudo,
+    /// The source text unchanged, tagged with the target language
+    Echo,
+}
+
+/// Settings of the offline pseudo-localization backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PseudoOptions {
+    pub mode: PseudoMode,
+    pub expansion_percent: u32, // Padding added, relative to the source length
+    pub latency_ms: u64,        // Simulated response time
+    pub error_percent: u8,      // Share of requests that fail
+    pub pairs: Vec<(String, String)>, // Direct pairs it claims to support, empty for all
+}
+
+impl Default for PseudoOptions {
+    fn default() -> Self {
+        PseudoOptions {
+            mode: PseudoMode::Pseudo,
+            expansion_percent: 30,
+            latency_ms: 0,
+            error_percent: 0,
+            pairs: Vec::new(),
+        }
+    }
+}
+
+impl PseudoOptions {
+    /// Produce the "translation" of a text
+    pub fn translate(&self, text: &str, target_lang: &str) -> String {
+        match self.mode {
+            PseudoMode::Pseudo => pseudo_localize(text, self.expansion_percent),
+            PseudoMode::Echo => format!("[{}] {}", target_lang, text),
+        }
+    }
+    
+    /// Check whether the backend claims to translate a pair directly, so
+    /// pivoting can be exercised offline by leaving pairs out
+    pub fn supports_pair(&self, source_lang: &str, target_lang: &str) -> bool {
+        self.pairs.is_empty() || self.pairs.iter()
+            .any(|(source, target)| (source == source_lang || source_lang == "auto") && target == target_lang)
+    }
+    
+    /// Wait for the configured latency, then fail the configured share of requests
+    pub async fn simulate_request(&self) -> Result<(), TranslationError> {
+        if self.latency_ms > 0 {
+            tokio::time::sleep(Duration::from_millis(self.latency_ms)).await;
+        }
+        
+        if self.error_percent == 0 {
+            return Ok(());
+        }
+        
+        let roll = next_random();
+        if roll % 100 >= self.error_percent as u64 {
+            return Ok(());
+        }
+        
+        // Cycle through the kinds of failure real services produce
+        Err(match (roll / 100) % 4 {
+            0 => TranslationError::Connection("Injected connection failure".to_string()),
+            1 => TranslationError::Timeout,
+            2 => TranslationError::RateLimited,
+            _ => TranslationError::Status(StatusCode::SERVICE_UNAVAILABLE),
+        })
+    }
+}
+
+/// Pseudo-localize a text: accent Latin letters, pad it to simulate the
+/// longer strings of other languages and wrap it in bracket markers, so
+/// untranslated, truncated and concatenated strings stand out.
+///
+/// Placeholders such as `{name}`, `%s` and markup tags are left untouched.
+pub fn pseudo_localize(text: &str, expansion_percent: u32) -> String {
+    let mut result = String::with_capacity(text.len() * 2 + 2);
+    result.push('[');
+    
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' | '<' => {
+                let close = if c == '{' { '}' } else { '>' };
+                result.push(c);
+                for inner in chars.by_ref() {
+                    result.push(inner);
+                    if inner == close {
+                        break;
+                    }
+                }
+            }
+            '%' => {
+                result.push(c);
+                if let Some(&next) = chars.peek() {
+                    if next.is_ascii_alphabetic() || next == '%' {
+                        result.push(next);
+                        chars.next();
+                    }
+                }
+            }
+            _ => result.push(accented(c)),
+        }
+    }
+    
+    let padding = (text.chars().count() * expansion_percent as usize).div_ceil(100);
+    if padding > 0 {
+        result.push(' ');
+        result.push_str(&"~".repeat(padding));
+    }
+    
+    result.push(']');
+    result
+}
+
+/// Accented look-alike of an ASCII letter
+fn accented(c: char) -> char {
+    match c {
+        'a' => 'á', 'b' => 'ƀ', 'c' => 'ç', 'd' => 'ð', 'e' => 'é',
+        'f' => 'ƒ', 'g' => 'ĝ', 'h' => 'ĥ', 'i' => 'í', 'j' => 'ĵ',
+        'k' => 'ķ', 'l' => 'ļ', 'm' => 'ɱ', 'n' => 'ñ', 'o' => 'ö',
+        'p' => 'þ', 'q' => 'ǫ', 'r' => 'ŕ', 's' => 'š', 't' => 'ţ',
+        'u' => 'ü', 'v' => 'ṽ', 'w' => 'ŵ', 'x' => 'ẋ', 'y' => 'ý',
+        'z' => 'ž',
+        'A' => 'Å', 'B' => 'Ɓ', 'C' => 'Ç', 'D' => 'Ð', 'E' => 'É',
+        'F' => 'Ƒ', 'G' => 'Ĝ', 'H' => 'Ĥ', 'I' => 'Î', 'J' => 'Ĵ',
+        'K' => 'Ķ', 'L' => 'Ļ', 'M' => 'Ṁ', 'N' => 'Ñ', 'O' => 'Ö',
+        'P' => 'Þ', 'Q' => 'Ǫ', 'R' => 'Ŕ', 'S' => 'Š', 'T' => 'Ţ',
+        'U' => 'Û', 'V' => 'Ṽ', 'W' => 'Ŵ', 'X' => 'Ẋ', 'Y' => 'Ý',
+        'Z' => 'Ž',
+        _ => c,
+    }
+}
+
+/// Next value of a small xorshift generator seeded from the clock
+fn next_random() -> u64 {
+    let mut state = RANDOM_STATE.load(Ordering::Relaxed);
+    if state == 0 {
+        state = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos() as u64)
+            .unwrap_or(0x2545_f491_4f6c_dd1d)
+            | 1;
+    }
+    
+    state ^= state << 13;
+    state ^= state >> 7;
+    state ^= state << 17;
+    RANDOM_STATE.store(state, Ordering::Relaxed);
+    
+    state
+}
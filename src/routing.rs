@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use crate::translation::TranslationService;
+
+/// Sends requests for matching language pairs and text lengths to a preferred service.
+///
+/// Language patterns are comma-separated codes such as `en,de`. `*` matches any
+/// language and a trailing `*` matches a prefix, e.g. `zh-*`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RoutingRule {
+    pub source_pattern: String,
+    pub target_pattern: String,
+    pub min_chars: Option<usize>,
+    pub max_chars: Option<usize>,
+    /// Also match the pair with source and target swapped
+    pub bidirectional: bool,
+    pub service: TranslationService,
+    pub enabled: bool,
+}
+
+impl Default for RoutingRule {
+    fn default() -> Self {
+        RoutingRule {
+            source_pattern: "*".to_string(),
+            target_pattern: "*".to_string(),
+            min_chars: None,
+            max_chars: None,
+            bidirectional: false,
+            service: TranslationService::GoogleBeta,
+            enabled: true,
+        }
+    }
+}
+
+impl fmt::Display for RoutingRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let arrow = if self.bidirectional { "↔" } else { "→" };
+        write!(f, "{} {} {}", self.source_pattern, arrow, self.target_pattern)?;
+        
+        match (self.min_chars, self.max_chars) {
+            (Some(min), Some(max)) => write!(f, ", {}–{} chars", min, max),
+            (Some(min), None) => write!(f, ", ≥ {} chars", min),
+            (None, Some(max)) => write!(f, ", ≤ {} chars", max),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+impl RoutingRule {
+    /// Check whether the rule applies to a request
+    pub fn matches(&self, source_lang: &str, target_lang: &str, text: &str) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        
+        let length = text.chars().count();
+        if self.min_chars.map_or(false, |min| length < min) || self.max_chars.map_or(false, |max| length > max) {
+            return false;
+        }
+        
+        let forward = pattern_matches(&self.source_pattern, source_lang)
+            && pattern_matches(&self.target_pattern, target_lang);
+        let backward = self.bidirectional
+            && pattern_matches(&self.source_pattern, target_lang)
+            && pattern_matches(&self.target_pattern, source_lang);
+        
+        forward || backward
+    }
+}
+
+/// Match a language code against a comma-separated pattern list.
+///
+/// Only `*` matches "auto", since the actual source language is unknown.
+fn pattern_matches(pattern: &str, code: &str) -> bool {
+    let code = code.to_lowercase();
+    
+    pattern.split(',')
+        .map(|part| part.trim().to_lowercase())
+        .filter(|part| !part.is_empty())
+        .any(|part| {
+            if part == "*" {
+                true
+            } else if code == "auto" {
+                false
+            } else if let Some(prefix) = part.strip_suffix('*') {
+                code.starts_with(prefix)
+            } else {
+                part == code
+            }
+        })
+}
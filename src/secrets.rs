@@ -0,0 +1,373 @@
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Marker at the start of the encrypted secrets file
+const FILE_MAGIC: &[u8] = b"SLSECRET1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// The store API keys are kept in, once it has been opened or unlocked
+static SECRET_STORE: Lazy<Mutex<Option<SecretStore>>> = Lazy::new(|| Mutex::new(None));
+
+/// API keys as last loaded from or saved to the store, so saving settings
+/// with unchanged keys does not touch the store
+static SYNCED_SECRETS: Lazy<Mutex<Option<HashMap<String, String>>>> = Lazy::new(|| Mutex::new(None));
+
+/// Storage for API keys, keyed by service configuration name
+pub enum SecretStore {
+    /// The freedesktop Secret Service (GNOME Keyring, KWallet, KeePassXC, ...)
+    #[cfg(feature = "secret-service")]
+    Keyring,
+    /// A file encrypted with a key derived from the user's passphrase
+    EncryptedFile { passphrase: String },
+}
+
+impl SecretStore {
+    /// Load all stored API keys
+    pub fn load(&self) -> Result<HashMap<String, String>, String> {
+        match self {
+            #[cfg(feature = "secret-service")]
+            SecretStore::Keyring => keyring::load(),
+            SecretStore::EncryptedFile { passphrase } => load_encrypted_file(passphrase),
+        }
+    }
+    
+    /// Replace the stored API keys with the given ones
+    pub fn store(&self, secrets: &HashMap<String, String>) -> Result<(), String> {
+        match self {
+            #[cfg(feature = "secret-service")]
+            SecretStore::Keyring => keyring::store(secrets),
+            SecretStore::EncryptedFile { passphrase } => store_encrypted_file(passphrase, secrets),
+        }
+    }
+}
+
+/// Open the Secret Service keyring if one is running on the session bus
+pub fn open_keyring() -> Option<SecretStore> {
+    #[cfg(feature = "secret-service")]
+    {
+        match keyring::check_available() {
+            Ok(()) => return Some(SecretStore::Keyring),
+            Err(e) => eprintln!("Secret Service not available: {}", e),
+        }
+    }
+    
+    None
+}
+
+/// Use the given store for loading and saving API keys
+pub fn set_store(store: Option<SecretStore>) {
+    if let Ok(mut current) = SECRET_STORE.lock() {
+        *current = store;
+        set_synced(None);
+    }
+}
+
+/// Check whether a store has been opened or unlocked
+pub fn is_unlocked() -> bool {
+    SECRET_STORE.lock().map(|store| store.is_some()).unwrap_or(false)
+}
+
+/// Load API keys from the current store, or `None` if no store is unlocked
+pub fn load() -> Option<Result<HashMap<String, String>, String>> {
+    let store = SECRET_STORE.lock().ok()?;
+    let result = store.as_ref()?.load();
+    if let Ok(secrets) = &result {
+        set_synced(Some(secrets.clone()));
+    }
+    
+    Some(result)
+}
+
+/// Save API keys to the current store, or `None` if no store is unlocked.
+/// Keys that match what the store already holds are not written again.
+pub fn store(secrets: &HashMap<String, String>) -> Option<Result<(), String>> {
+    let store = SECRET_STORE.lock().ok()?;
+    let store = store.as_ref()?;
+    
+    let unchanged = SYNCED_SECRETS.lock()
+        .map(|synced| synced.as_ref() == Some(secrets))
+        .unwrap_or(false);
+    if unchanged {
+        return Some(Ok(()));
+    }
+    
+    let result = store.store(secrets);
+    if result.is_ok() {
+        set_synced(Some(secrets.clone()));
+    }
+    
+    Some(result)
+}
+
+/// Remember the keys the store holds
+fn set_synced(secrets: Option<HashMap<String, String>>) {
+    if let Ok(mut synced) = SYNCED_SECRETS.lock() {
+        *synced = secrets;
+    }
+}
+
+/// Check whether keys were previously saved to the encrypted file
+pub fn encrypted_file_exists() -> bool {
+    encrypted_file_path().exists()
+}
+
+/// Path of the encrypted secrets file, next to settings.json
+fn encrypted_file_path() -> PathBuf {
+    let home_dir = env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(format!("{}/.config/translator-app/secrets.enc", home_dir))
+}
+
+/// Derive the file encryption key from the passphrase with Argon2
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key, String> {
+    let mut key = Key::default();
+    argon2::Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Could not derive encryption key: {}", e))?;
+    Ok(key)
+}
+
+/// Decrypt the secrets file; a missing file means no keys were stored yet
+fn load_encrypted_file(passphrase: &str) -> Result<HashMap<String, String>, String> {
+    let path = encrypted_file_path();
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    
+    let data = fs::read(&path)
+        .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    
+    // Layout: magic | salt | nonce | ciphertext
+    let header_len = FILE_MAGIC.len() + SALT_LEN + NONCE_LEN;
+    if data.len() < header_len || !data.starts_with(FILE_MAGIC) {
+        return Err(format!("{} is not a SwiftLingo secrets file", path.display()));
+    }
+    
+    let salt = &data[FILE_MAGIC.len()..FILE_MAGIC.len() + SALT_LEN];
+    let nonce = Nonce::from_slice(&data[FILE_MAGIC.len() + SALT_LEN..header_len]);
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, salt)?);
+    
+    let plaintext = cipher.decrypt(nonce, &data[header_len..])
+        .map_err(|_| "Wrong passphrase or corrupted secrets file".to_string())?;
+    
+    serde_json::from_slice(&plaintext)
+        .map_err(|e| format!("Could not parse stored secrets: {}", e))
+}
+
+/// Encrypt the secrets with a fresh salt and nonce and write them to the file
+fn store_encrypted_file(passphrase: &str, secrets: &HashMap<String, String>) -> Result<(), String> {
+    let plaintext = serde_json::to_vec(secrets)
+        .map_err(|e| format!("Could not serialize secrets: {}", e))?;
+    
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    
+    let ciphertext = cipher.encrypt(&nonce, plaintext.as_slice())
+        .map_err(|_| "Could not encrypt secrets".to_string())?;
+    
+    let mut data = Vec::with_capacity(FILE_MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    data.extend_from_slice(FILE_MAGIC);
+    data.extend_from_slice(&salt);
+    data.extend_from_slice(&nonce);
+    data.extend_from_slice(&ciphertext);
+    
+    let path = encrypted_file_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Could not create {}: {}", dir.display(), e))?;
+    }
+    
+    write_private_file(&path, &data)
+        .map_err(|e| format!("Could not write {}: {}", path.display(), e))
+}
+
+/// Write a file readable only by the current user
+fn write_private_file(path: &PathBuf, data: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+    
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(data)
+}
+
+/// Access to the freedesktop Secret Service over D-Bus
+#[cfg(feature = "secret-service")]
+mod keyring {
+    use std::collections::HashMap;
+    use zbus::blocking::{Connection, Proxy};
+    use zbus::zvariant::{OwnedObjectPath, OwnedValue, Value};
+    
+    const SERVICE_NAME: &str = "org.freedesktop.secrets";
+    const SERVICE_PATH: &str = "/org/freedesktop/secrets";
+    const DEFAULT_COLLECTION: &str = "/org/freedesktop/secrets/aliases/default";
+    
+    /// Attribute marking items created by SwiftLingo
+    const APP_ATTRIBUTE: (&str, &str) = ("application", "swiftlingo");
+    
+    /// Attribute holding the service configuration name
+    const SERVICE_ATTRIBUTE: &str = "service";
+    
+    /// A secret as transferred by the Secret Service: (session, parameters, value, content type)
+    type Secret = (OwnedObjectPath, Vec<u8>, Vec<u8>, String);
+    
+    /// An open connection with a plain-text transfer session
+    struct Session {
+        connection: Connection,
+        path: OwnedObjectPath,
+    }
+    
+    impl Session {
+        fn open() -> zbus::Result<Self> {
+            let connection = Connection::session()?;
+            let service = service_proxy(&connection)?;
+            // Secrets travel over the private session bus, so no transport encryption is used
+            let (_, path): (OwnedValue, OwnedObjectPath) = service.call("OpenSession", &("plain", Value::from("")))?;
+            Ok(Session { connection, path })
+        }
+        
+        /// Find SwiftLingo items, unlocking them if needed
+        fn find_items(&self, service_name: Option<&str>) -> zbus::Result<Vec<OwnedObjectPath>> {
+            let mut attributes = HashMap::from([APP_ATTRIBUTE]);
+            if let Some(name) = service_name {
+                attributes.insert(SERVICE_ATTRIBUTE, name);
+            }
+            
+            let service = service_proxy(&self.connection)?;
+            let (mut unlocked, locked): (Vec<OwnedObjectPath>, Vec<OwnedObjectPath>) =
+                service.call("SearchItems", &(attributes,))?;
+            
+            if !locked.is_empty() {
+                let (newly_unlocked, prompt): (Vec<OwnedObjectPath>, OwnedObjectPath) =
+                    service.call("Unlock", &(locked.clone(),))?;
+                unlocked.extend(newly_unlocked);
+                if prompt.as_str() != "/" {
+                    self.run_prompt(&prompt)?;
+                    unlocked.extend(locked);
+                }
+            }
+            
+            Ok(unlocked)
+        }
+        
+        /// Show a keyring prompt (e.g. to unlock the collection) and wait for it to finish
+        fn run_prompt(&self, prompt_path: &OwnedObjectPath) -> zbus::Result<()> {
+            let prompt = Proxy::new(&self.connection, SERVICE_NAME, prompt_path.as_str(), "org.freedesktop.Secret.Prompt")?;
+            let mut completed = prompt.receive_signal("Completed")?;
+            prompt.call_method("Prompt", &("",))?;
+            
+            if let Some(message) = completed.next() {
+                let (dismissed, _): (bool, OwnedValue) = message.body()?;
+                if dismissed {
+                    return Err(zbus::Error::Failure("Keyring prompt was dismissed".to_string()));
+                }
+            }
+            
+            Ok(())
+        }
+        
+        fn get_secret(&self, item_path: &OwnedObjectPath) -> zbus::Result<(String, String)> {
+            let item = item_proxy(&self.connection, item_path)?;
+            let attributes: HashMap<String, String> = item.get_property("Attributes")?;
+            let (_, _, value, _): Secret = item.call("GetSecret", &(&self.path,))?;
+            
+            let service_name = attributes.get(SERVICE_ATTRIBUTE).cloned().unwrap_or_default();
+            Ok((service_name, String::from_utf8_lossy(&value).into_owned()))
+        }
+        
+        fn create_item(&self, service_name: &str, value: &str) -> zbus::Result<()> {
+            let collection = Proxy::new(&self.connection, SERVICE_NAME, DEFAULT_COLLECTION, "org.freedesktop.Secret.Collection")?;
+            
+            let attributes = HashMap::from([APP_ATTRIBUTE, (SERVICE_ATTRIBUTE, service_name)]);
+            let label = format!("SwiftLingo API key ({})", service_name);
+            let properties = HashMap::from([
+                ("org.freedesktop.Secret.Item.Label", Value::from(label)),
+                ("org.freedesktop.Secret.Item.Attributes", Value::from(attributes)),
+            ]);
+            let secret: Secret = (
+                self.path.clone(),
+                Vec::new(),
+                value.as_bytes().to_vec(),
+                "text/plain".to_string(),
+            );
+            
+            let (_, prompt): (OwnedObjectPath, OwnedObjectPath) =
+                collection.call("CreateItem", &(properties, secret, true))?;
+            if prompt.as_str() != "/" {
+                self.run_prompt(&prompt)?;
+            }
+            
+            Ok(())
+        }
+        
+        fn delete_item(&self, item_path: &OwnedObjectPath) -> zbus::Result<()> {
+            let item = item_proxy(&self.connection, item_path)?;
+            let prompt: OwnedObjectPath = item.call("Delete", &())?;
+            if prompt.as_str() != "/" {
+                self.run_prompt(&prompt)?;
+            }
+            
+            Ok(())
+        }
+    }
+    
+    fn service_proxy(connection: &Connection) -> zbus::Result<Proxy<'static>> {
+        Proxy::new(connection, SERVICE_NAME, SERVICE_PATH, "org.freedesktop.Secret.Service")
+    }
+    
+    fn item_proxy(connection: &Connection, path: &OwnedObjectPath) -> zbus::Result<Proxy<'static>> {
+        Proxy::new(connection, SERVICE_NAME, path.to_string(), "org.freedesktop.Secret.Item")
+    }
+    
+    /// Check that a Secret Service is running by opening a session
+    pub fn check_available() -> Result<(), String> {
+        Session::open().map(|_| ()).map_err(|e| e.to_string())
+    }
+    
+    pub fn load() -> Result<HashMap<String, String>, String> {
+        let session = Session::open().map_err(|e| e.to_string())?;
+        let items = session.find_items(None).map_err(|e| e.to_string())?;
+        
+        let mut secrets = HashMap::new();
+        for item in items {
+            let (service_name, value) = session.get_secret(&item).map_err(|e| e.to_string())?;
+            if !service_name.is_empty() {
+                secrets.insert(service_name, value);
+            }
+        }
+        
+        Ok(secrets)
+    }
+    
+    pub fn store(secrets: &HashMap<String, String>) -> Result<(), String> {
+        let session = Session::open().map_err(|e| e.to_string())?;
+        
+        // Remove keys that were cleared in the settings
+        let stored = load()?;
+        for service_name in stored.keys().filter(|name| !secrets.contains_key(*name)) {
+            for item in session.find_items(Some(service_name)).map_err(|e| e.to_string())? {
+                session.delete_item(&item).map_err(|e| e.to_string())?;
+            }
+        }
+        
+        for (service_name, value) in secrets {
+            if stored.get(service_name) != Some(value) {
+                session.create_item(service_name, value).map_err(|e| e.to_string())?;
+            }
+        }
+        
+        Ok(())
+    }
+}
@@ -4,11 +4,27 @@ use std::env;
 use std::fs;
 use std::io::{Read, Write};
 use std::path::Path;
-use crate::translation::{TranslationService, ServiceConfig};
+use crate::translation::{TranslationService, ServiceConfig, LINGVA_DEFAULT_INSTANCE};
+use crate::tts::{TtsEngine, TtsOptions};
+use crate::dictation::WhisperOptions;
+use crate::secrets;
+use crate::routing::RoutingRule;
+use crate::database::RetentionPolicy;
+
+/// Format version of settings.json, bumped when `migrate` has to rewrite values
+const SETTINGS_VERSION: u32 = 1;
+
+/// History entries shown when no entry limit is set
+const DEFAULT_HISTORY_VIEW_LIMIT: i64 = 100;
 
 /// Application settings including appearance, defaults, and API configurations
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Settings {
+    // Missing in files written before versioning was added
+    #[serde(default)]
+    pub settings_version: u32,
+    
     // General settings
     pub dark_mode: bool,
     pub default_source_lang: String,
@@ -18,13 +34,46 @@ pub struct Settings {
     pub window_x: Option<i32>,
     pub window_y: Option<i32>,
     pub startup_minimized: bool,
+    pub show_transliteration: bool,
+    
+    // Interface language code from po/LINGUAS, None to follow the system locale
+    pub ui_language: Option<String>,
+    
+    // Translate between these two when the text is already in the target language
+    pub auto_swap_languages: bool,
+    pub primary_language: String,
+    pub secondary_language: String,
     
     // Translation service settings
     pub active_service: TranslationService,
     pub service_configs: HashMap<String, ServiceConfig>,
+    pub pivot_language: String,
+    
+    // Rules picking a service by language pair and text length, checked in order
+    pub routing_rules: Vec<RoutingRule>,
     
-    // History settings
+    // Try all available services, healthiest first, when the selected one fails
+    pub health_ordered_fallback: bool,
+    
+    // Usage budgets in characters per month, by service configuration name
+    pub monthly_budgets: HashMap<String, u64>,
+    pub usage_warning_percent: u8,
+    pub switch_service_over_budget: bool,
+    
+    // Text-to-speech settings
+    pub tts_engine: TtsEngine,
+    pub tts_voices: HashMap<String, String>,
+    pub tts_online_fallback: bool,
+    
+    // Speech input settings
+    pub whisper_binary: String,
+    pub whisper_model: Option<String>,
+    
+    // History settings, 0 meaning no limit. Pinned entries and entries in a
+    // list are always kept.
     pub max_history_entries: i32,
+    pub max_history_age_days: u32,
+    pub max_history_size_mb: u32,
     pub auto_save_history: bool,
 }
 
@@ -39,6 +88,7 @@ impl Default for Settings {
                 api_key: None,
                 endpoint: None,
                 timeout_seconds: Some(5),
+                ..ServiceConfig::default()
             },
         );
         
@@ -49,6 +99,17 @@ impl Default for Settings {
                 api_key: None,
                 endpoint: None,
                 timeout_seconds: Some(5),
+                ..ServiceConfig::default()
+            },
+        );
+        
+        service_configs.insert(
+            "GoogleCloudV3".to_string(),
+            ServiceConfig {
+                api_key: None,
+                endpoint: None,
+                timeout_seconds: Some(5),
+                ..ServiceConfig::default()
             },
         );
         
@@ -58,6 +119,7 @@ impl Default for Settings {
                 api_key: None,
                 endpoint: Some("https://libretranslate.com/translate".to_string()),
                 timeout_seconds: Some(5),
+                ..ServiceConfig::default()
             },
         );
         
@@ -67,6 +129,7 @@ impl Default for Settings {
                 api_key: None,
                 endpoint: None,
                 timeout_seconds: Some(5),
+                ..ServiceConfig::default()
             },
         );
         
@@ -76,10 +139,37 @@ impl Default for Settings {
                 api_key: None,
                 endpoint: None,
                 timeout_seconds: Some(5),
+                ..ServiceConfig::default()
+            },
+        );
+        
+        service_configs.insert(
+            "Lingva".to_string(),
+            ServiceConfig {
+                api_key: None,
+                endpoint: Some(LINGVA_DEFAULT_INSTANCE.to_string()),
+                timeout_seconds: Some(5),
+                ..ServiceConfig::default()
+            },
+        );
+        
+        service_configs.insert(
+            "MyMemory".to_string(),
+            ServiceConfig {
+                api_key: None,
+                endpoint: None,
+                timeout_seconds: Some(5),
+                ..ServiceConfig::default()
             },
         );
         
+        service_configs.insert(
+            "Pseudo".to_string(),
+            ServiceConfig::default(),
+        );
+        
         Settings {
+            settings_version: SETTINGS_VERSION,
             dark_mode: false,
             default_source_lang: "auto".to_string(),
             default_target_lang: "en".to_string(),
@@ -88,15 +178,50 @@ impl Default for Settings {
             window_x: None,
             window_y: None,
             startup_minimized: false,
+            show_transliteration: false,
+            ui_language: None,
+            auto_swap_languages: true,
+            primary_language: "en".to_string(),
+            secondary_language: "es".to_string(),
             active_service: TranslationService::GoogleBeta,
             service_configs,
-            max_history_entries: 100,
+            pivot_language: "en".to_string(),
+            routing_rules: Vec::new(),
+            health_ordered_fallback: false,
+            monthly_budgets: HashMap::new(),
+            usage_warning_percent: 80,
+            switch_service_over_budget: false,
+            tts_engine: TtsEngine::EspeakNg,
+            tts_voices: HashMap::new(),
+            tts_online_fallback: false,
+            whisper_binary: "whisper-cli".to_string(),
+            whisper_model: None,
+            max_history_entries: 0,
+            max_history_age_days: 0,
+            max_history_size_mb: 0,
             auto_save_history: true,
         }
     }
 }
 
 impl Settings {
+    /// Target to use when the source language equals the target, which would
+    /// make the translation a no-op: the secondary language for text in the
+    /// primary language and the primary language for anything else
+    pub fn swapped_target(&self, source_lang: &str, target_lang: &str) -> Option<String> {
+        if !self.auto_swap_languages || source_lang != target_lang {
+            return None;
+        }
+        
+        let target = if source_lang == self.primary_language {
+            &self.secondary_language
+        } else {
+            &self.primary_language
+        };
+        
+        (target != target_lang).then(|| target.clone())
+    }
+    
     /// Load settings from file or create with defaults
     pub fn load() -> Self {
         let config_path = Self::get_config_path();
@@ -106,7 +231,8 @@ impl Settings {
                 Ok(mut file) => {
                     let mut contents = String::new();
                     if file.read_to_string(&mut contents).is_ok() {
-                        if let Ok(settings) = serde_json::from_str(&contents) {
+                        if let Ok(mut settings) = serde_json::from_str::<Settings>(&contents) {
+                            settings.migrate();
                             return settings;
                         }
                     }
@@ -121,7 +247,32 @@ impl Settings {
         default_settings
     }
     
+    /// Move values saved by older versions to their current fields
+    fn migrate(&mut self) {
+        // The Azure region used to be stored in the Bing endpoint field
+        if let Some(config) = self.service_configs.get_mut(TranslationService::Bing.config_name()) {
+            let endpoint_is_region = config.endpoint.as_deref()
+                .map_or(false, |endpoint| !endpoint.is_empty() && !endpoint.contains("://"));
+            
+            if endpoint_is_region && config.region.is_none() {
+                config.region = config.endpoint.take();
+            }
+        }
+        
+        // Version 1 enforces the history limits. The entry limit written by
+        // older versions was never applied, so drop it rather than delete the
+        // history of existing users.
+        if self.settings_version < 1 {
+            self.max_history_entries = 0;
+        }
+        
+        self.settings_version = SETTINGS_VERSION;
+    }
+    
     /// Save settings to file
+    ///
+    /// API keys go to the unlocked secret store and are left out of the JSON file.
+    /// They stay in the file only while no store is available.
     pub fn save(&self) -> bool {
         let config_path = Self::get_config_path();
         let config_dir = Path::new(&config_path).parent().unwrap();
@@ -130,7 +281,18 @@ impl Settings {
             return false;
         }
         
-        match serde_json::to_string_pretty(self) {
+        let mut file_settings = self.clone();
+        match secrets::store(&self.api_keys()) {
+            Some(Ok(())) => {
+                for config in file_settings.service_configs.values_mut() {
+                    config.api_key = None;
+                }
+            },
+            Some(Err(e)) => eprintln!("Could not store API keys securely, keeping them in settings.json: {}", e),
+            None => {}
+        }
+        
+        match serde_json::to_string_pretty(&file_settings) {
             Ok(json) => {
                 match fs::File::create(&config_path) {
                     Ok(mut file) => {
@@ -147,6 +309,52 @@ impl Settings {
         false
     }
     
+    /// Fill in API keys from the unlocked secret store.
+    ///
+    /// Plaintext keys found in settings.json are moved to the store.
+    pub fn load_secrets(&mut self) -> Result<(), String> {
+        let stored = match secrets::load() {
+            Some(result) => result?,
+            None => return Err("No secret store is unlocked".to_string()),
+        };
+        
+        let has_plaintext_keys = !self.api_keys().is_empty();
+        
+        for (service_name, api_key) in stored {
+            let config = self.service_configs.entry(service_name).or_default();
+            // A key still in settings.json is newer than the stored one
+            if config.api_key.as_deref().map_or(true, str::is_empty) {
+                config.api_key = Some(api_key);
+            }
+        }
+        
+        if has_plaintext_keys {
+            println!("Migrating API keys from settings.json to the secret store");
+            self.save();
+        }
+        
+        Ok(())
+    }
+    
+    /// Check whether settings.json still holds any API keys in plain text
+    pub fn has_plaintext_keys() -> bool {
+        fs::read_to_string(Self::get_config_path())
+            .ok()
+            .and_then(|contents| serde_json::from_str::<Settings>(&contents).ok())
+            .map_or(false, |settings| !settings.api_keys().is_empty())
+    }
+    
+    /// Non-empty API keys by service configuration name
+    fn api_keys(&self) -> HashMap<String, String> {
+        self.service_configs.iter()
+            .filter_map(|(name, config)| {
+                config.api_key.as_ref()
+                    .filter(|key| !key.is_empty())
+                    .map(|key| (name.clone(), key.clone()))
+            })
+            .collect()
+    }
+    
     /// Get configuration file path
     fn get_config_path() -> String {
         let home_dir = env::var("HOME").unwrap_or_else(|_| ".".to_string());
@@ -159,17 +367,54 @@ impl Settings {
         self.service_configs.insert(service_name.to_string(), config);
     }
     
-    #[allow(dead_code)]
     pub fn get_service_config(&self, service: &TranslationService) -> Option<ServiceConfig> {
-        let service_name = match service {
-            TranslationService::GoogleBeta => "GoogleBeta",
-            TranslationService::GoogleOfficial => "GoogleOfficial",
-            TranslationService::LibreTranslate => "LibreTranslate",
-            TranslationService::Bing => "Bing",
-            TranslationService::DeepL => "DeepL",
-        };
+        self.service_configs.get(service.config_name()).cloned()
+    }
+    
+    /// Monthly character budget of a service, `None` if unlimited
+    pub fn monthly_budget(&self, service: &TranslationService) -> Option<u64> {
+        self.monthly_budgets.get(service.config_name())
+            .copied()
+            .filter(|budget| *budget > 0)
+    }
+    
+    /// Text-to-speech options for the configured engine and voices
+    pub fn tts_options(&self) -> TtsOptions {
+        TtsOptions {
+            engine: self.tts_engine.clone(),
+            voices: self.tts_voices.clone(),
+            online_fallback: self.tts_online_fallback,
+            // The online voice is Google's, reached like the keyless Google service
+            online_config: self.get_service_config(&TranslationService::GoogleBeta).unwrap_or_default(),
+        }
+    }
+    
+    /// whisper.cpp options for speech input
+    pub fn whisper_options(&self) -> WhisperOptions {
+        WhisperOptions {
+            binary: self.whisper_binary.clone(),
+            model: self.whisper_model.clone(),
+        }
+    }
+    
+    /// Limits applied when pruning the history
+    pub fn retention_policy(&self) -> RetentionPolicy {
+        let limit = |value: i64| (value > 0).then_some(value);
         
-        self.service_configs.get(service_name).cloned()
+        RetentionPolicy {
+            max_entries: limit(self.max_history_entries as i64),
+            max_age_days: limit(self.max_history_age_days as i64),
+            max_size_bytes: limit(self.max_history_size_mb as i64 * 1024 * 1024),
+        }
+    }
+    
+    /// Number of entries the history view shows
+    pub fn history_view_limit(&self) -> i64 {
+        if self.max_history_entries > 0 {
+            self.max_history_entries as i64
+        } else {
+            DEFAULT_HISTORY_VIEW_LIMIT
+        }
     }
     
     #[allow(dead_code)]
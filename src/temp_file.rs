@@ -0,0 +1,57 @@
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::ErrorKind;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Counter making the names of this process's files unique
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A private temporary file for audio handed to external tools, removed when dropped
+#[derive(Debug)]
+pub struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    /// Create an empty file named like "swiftlingo-tts-1234-0.wav" that only
+    /// the user can read, in $XDG_RUNTIME_DIR or else the temp directory.
+    ///
+    /// The file is created exclusively, so an existing file or symlink with
+    /// the same name is never reused.
+    pub fn new(prefix: &str, extension: &str) -> Result<Self, String> {
+        let dir = env::var_os("XDG_RUNTIME_DIR")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_dir())
+            .unwrap_or_else(env::temp_dir);
+        
+        loop {
+            let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+            let path = dir.join(format!("swiftlingo-{}-{}-{}.{}", prefix, process::id(), id, extension));
+            
+            match OpenOptions::new().write(true).create_new(true).mode(0o600).open(&path) {
+                Ok(_) => return Ok(TempFile { path }),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(format!("Could not create a temporary file in {}: {}", dir.display(), e)),
+            }
+        }
+    }
+    
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl AsRef<Path> for TempFile {
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
@@ -1,18 +1,34 @@
-use reqwest::Client;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Certificate, Client, Proxy, StatusCode};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use urlencoding::encode;
+use gettextrs::gettext;
+use crate::dictionary::{is_dictionary_query, DictionaryResult};
+use crate::google_auth::{self, ServiceAccountKey};
+use crate::detection;
+use crate::health::HealthTracker;
+use crate::i18n::gettext_f;
+use crate::languages;
+use crate::pseudo::PseudoOptions;
+use crate::routing::RoutingRule;
+use crate::settings::Settings;
+use crate::transliteration::transliterate;
 
 /// Represents available translation services
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TranslationService {
     GoogleBeta,    // Current implementation (free)
     GoogleOfficial, // Official Google API (requires key)
+    GoogleCloudV3,  // Cloud Translation v3 (service account)
     LibreTranslate, // Open-source alternative
     Bing,          // Microsoft Translator
     DeepL,         // DeepL API
+    Lingva,        // Google Translate front-end (no key)
+    MyMemory,      // Translation memory API (no key)
+    Pseudo,        // Offline pseudo-localization for testing
 }
 
 impl fmt::Display for TranslationService {
@@ -20,44 +36,172 @@ impl fmt::Display for TranslationService {
         match self {
             TranslationService::GoogleBeta => write!(f, "Google Translate (Beta)"),
             TranslationService::GoogleOfficial => write!(f, "Google Translate (Official)"),
+            TranslationService::GoogleCloudV3 => write!(f, "Google Cloud Translation (v3)"),
             TranslationService::LibreTranslate => write!(f, "LibreTranslate"),
             TranslationService::Bing => write!(f, "Bing Translator"),
             TranslationService::DeepL => write!(f, "DeepL"),
+            TranslationService::Lingva => write!(f, "Lingva Translate"),
+            TranslationService::MyMemory => write!(f, "MyMemory"),
+            TranslationService::Pseudo => write!(f, "Pseudo-localization (offline)"),
         }
     }
 }
 
 impl TranslationService {
     /// Get service name for configuration lookup
-    #[allow(dead_code)]
     pub fn config_name(&self) -> &'static str {
         match self {
             TranslationService::GoogleBeta => "GoogleBeta",
             TranslationService::GoogleOfficial => "GoogleOfficial",
+            TranslationService::GoogleCloudV3 => "GoogleCloudV3",
             TranslationService::LibreTranslate => "LibreTranslate",
             TranslationService::Bing => "Bing",
             TranslationService::DeepL => "DeepL",
+            TranslationService::Lingva => "Lingva",
+            TranslationService::MyMemory => "MyMemory",
+            TranslationService::Pseudo => "Pseudo",
         }
     }
     
+    /// Look up a service by its configuration name
+    pub fn from_config_name(name: &str) -> Option<TranslationService> {
+        TranslationService::all_services()
+            .into_iter()
+            .find(|service| service.config_name() == name)
+    }
+    
+    /// Check whether a configuration has the credentials the service needs
+    pub fn is_configured(&self, config: &ServiceConfig) -> bool {
+        let is_set = |value: &Option<String>| value.as_deref().map_or(false, |value| !value.is_empty());
+        
+        match self {
+            TranslationService::GoogleOfficial | TranslationService::Bing | TranslationService::DeepL => is_set(&config.api_key),
+            TranslationService::GoogleCloudV3 => is_set(&config.credentials_path),
+            _ => true,
+        }
+    }
+    
+    /// Language codes the service can translate between, or `None` if it
+    /// accepts every language in `LANGUAGES`
+    pub fn supported_languages(&self) -> Option<&'static [&'static str]> {
+        match self {
+            TranslationService::LibreTranslate => Some(LIBRETRANSLATE_LANGUAGES),
+            TranslationService::DeepL => Some(DEEPL_LANGUAGES),
+            _ => None,
+        }
+    }
+    
+    /// Check whether the service can translate directly between two languages
+    pub fn supports_pair(&self, config: &ServiceConfig, source_lang: &str, target_lang: &str) -> bool {
+        if *self == TranslationService::Pseudo {
+            return target_lang != "auto" && config.pseudo.supports_pair(source_lang, target_lang);
+        }
+        
+        let supports = |code: &str| {
+            self.supported_languages()
+                .map_or(true, |languages| languages.contains(&code))
+        };
+        
+        let source_supported = if source_lang == "auto" {
+            self.detects_language()
+        } else {
+            supports(source_lang)
+        };
+        
+        source_supported && target_lang != "auto" && supports(target_lang)
+    }
+    
+    /// Check whether the service can detect the source language itself
+    pub fn detects_language(&self) -> bool {
+        !matches!(self, TranslationService::MyMemory)
+    }
+    
+    /// Whether requests must name the source language, so "auto" has to be
+    /// replaced by a local guess. Cloud Translation v3 only applies a
+    /// glossary when the source language is given.
+    pub fn needs_source_language(&self, config: &ServiceConfig) -> bool {
+        let has_glossary = config.glossary.as_deref().map_or(false, |glossary| !glossary.is_empty());
+        
+        !self.detects_language() || (*self == TranslationService::GoogleCloudV3 && has_glossary)
+    }
+    
+    /// Whether the service answers without the network and never really translates
+    pub fn is_offline(&self) -> bool {
+        matches!(self, TranslationService::Pseudo)
+    }
+    
     /// Get all available services
     pub fn all_services() -> Vec<TranslationService> {
         vec![
             TranslationService::GoogleBeta,
             TranslationService::GoogleOfficial,
+            TranslationService::GoogleCloudV3,
             TranslationService::LibreTranslate,
             TranslationService::Bing,
             TranslationService::DeepL,
+            TranslationService::Lingva,
+            TranslationService::MyMemory,
+            TranslationService::Pseudo,
         ]
     }
 }
 
+/// Languages offered by the public LibreTranslate instance
+const LIBRETRANSLATE_LANGUAGES: &[&str] = &[
+    "ar", "az", "bg", "bn", "ca", "cs", "da", "de", "el", "en", "eo", "es", "et",
+    "eu", "fa", "fi", "fr", "ga", "gl", "he", "hi", "hu", "id", "it", "ja", "ko",
+    "lt", "lv", "ms", "nl", "no", "pl", "pt", "ro", "ru", "sk", "sl", "sq", "sv",
+    "th", "tl", "tr", "uk", "ur", "zh-CN", "zh-TW",
+];
+
+/// Languages supported by the DeepL API
+const DEEPL_LANGUAGES: &[&str] = &[
+    "ar", "bg", "cs", "da", "de", "el", "en", "es", "et", "fi", "fr", "hu", "id",
+    "it", "ja", "ko", "lt", "lv", "nl", "no", "pl", "pt", "ro", "ru", "sk", "sl",
+    "sv", "tr", "uk", "zh-CN",
+];
+
+/// Public Lingva instance used when no other instance is configured
+pub const LINGVA_DEFAULT_INSTANCE: &str = "https://lingva.ml";
+
+/// Global Azure Translator endpoint
+const AZURE_TRANSLATOR_ENDPOINT: &str = "https://api.cognitive.microsofttranslator.com";
+
+/// MyMemory translation endpoint
+const MYMEMORY_ENDPOINT: &str = "https://api.mymemory.translated.net/get";
+
+/// How many dictionary translations to fetch usage examples for
+const MAX_AZURE_EXAMPLE_TRANSLATIONS: usize = 3;
+
 /// Configuration for a translation service
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ServiceConfig {
     pub api_key: Option<String>,
     pub endpoint: Option<String>,
     pub timeout_seconds: Option<u64>,
+    
+    // HTTP client settings
+    pub proxy_url: Option<String>,       // http(s):// or socks5://, optionally with user:password@
+    pub ca_cert_paths: Vec<String>,      // Extra PEM root certificates
+    pub headers: HashMap<String, String>,
+    pub user_agent: Option<String>,
+    
+    // Azure resource region, e.g. "westeurope"
+    pub region: Option<String>,
+    
+    // Contact email, raising the MyMemory daily quota
+    pub email: Option<String>,
+    
+    // Google Cloud Translation v3 settings
+    pub credentials_path: Option<String>, // Service-account JSON key file
+    pub project_id: Option<String>,       // Defaults to the project of the service account
+    pub location: Option<String>,         // "global" unless a glossary or model needs a region
+    pub glossary: Option<String>,
+    pub model: Option<String>,            // e.g. "general/nmt", "general/translation-llm"
+    
+    // Offline pseudo-localization backend settings
+    pub pseudo: PseudoOptions,
 }
 
 impl Default for ServiceConfig {
@@ -66,6 +210,158 @@ impl Default for ServiceConfig {
             api_key: None,
             endpoint: None,
             timeout_seconds: Some(5),
+            proxy_url: None,
+            ca_cert_paths: Vec::new(),
+            headers: HashMap::new(),
+            user_agent: None,
+            region: None,
+            email: None,
+            credentials_path: None,
+            project_id: None,
+            location: None,
+            glossary: None,
+            model: None,
+            pseudo: PseudoOptions::default(),
+        }
+    }
+}
+
+impl ServiceConfig {
+    /// Build an HTTP client with this service's proxy, certificates, headers and timeout
+    pub fn build_client(&self) -> Result<Client, String> {
+        let mut builder = Client::builder()
+            .timeout(Duration::from_secs(self.timeout_seconds.unwrap_or(10)))
+            .connect_timeout(Duration::from_secs(5));
+        
+        // Credentials in the proxy URL are used for proxy authentication
+        if let Some(proxy_url) = self.proxy_url.as_deref().filter(|url| !url.is_empty()) {
+            let proxy = Proxy::all(proxy_url)
+                .map_err(|e| format!("Invalid proxy URL '{}': {}", proxy_url, e))?;
+            builder = builder.proxy(proxy);
+        }
+        
+        for path in &self.ca_cert_paths {
+            let pem = std::fs::read(path)
+                .map_err(|e| format!("Could not read CA certificate '{}': {}", path, e))?;
+            let certificate = Certificate::from_pem(&pem)
+                .map_err(|e| format!("Invalid CA certificate '{}': {}", path, e))?;
+            builder = builder.add_root_certificate(certificate);
+        }
+        
+        if !self.headers.is_empty() {
+            let mut header_map = HeaderMap::new();
+            for (name, value) in &self.headers {
+                let header_name = HeaderName::from_bytes(name.as_bytes())
+                    .map_err(|_| format!("Invalid header name '{}'", name))?;
+                let header_value = HeaderValue::from_str(value)
+                    .map_err(|_| format!("Invalid value for header '{}'", name))?;
+                header_map.insert(header_name, header_value);
+            }
+            builder = builder.default_headers(header_map);
+        }
+        
+        if let Some(user_agent) = self.user_agent.as_deref().filter(|ua| !ua.is_empty()) {
+            builder = builder.user_agent(user_agent);
+        }
+        
+        builder.build()
+            .map_err(|e| format!("Could not create HTTP client: {}", e))
+    }
+}
+
+/// Intermediate step taken when a pair is routed through a pivot language
+#[derive(Debug, Clone)]
+pub struct PivotStep {
+    pub language: String,
+    pub text: String,
+}
+
+/// Outcome of a translation request
+#[derive(Debug, Clone, Default)]
+pub struct TranslationResult {
+    pub text: String,
+    pub pivot: Option<PivotStep>,
+    pub dictionary: Option<DictionaryResult>,
+    pub source_transliteration: Option<String>,
+    pub transliteration: Option<String>,
+    /// Service that produced the translation
+    pub service: Option<TranslationService>,
+    /// Characters sent to the service, counting both legs of a pivot
+    pub billed_characters: usize,
+    /// Routing rule that picked the service, if any
+    pub routing_rule: Option<RoutingRule>,
+}
+
+impl TranslationResult {
+    fn direct(text: String) -> Self {
+        TranslationResult { text, ..Default::default() }
+    }
+}
+
+/// Errors reported by translation services
+#[derive(Debug, Clone)]
+pub enum TranslationError {
+    /// A required setting such as the API key or endpoint is missing
+    NotConfigured(String),
+    /// The HTTP client could not be built from the service configuration
+    InvalidConfig(String),
+    /// The service could not be reached
+    Connection(String),
+    /// The service did not answer within the configured timeout
+    Timeout,
+    /// The service rejected the API key
+    Unauthorized,
+    /// The service cannot handle the requested languages
+    UnsupportedLanguage(String),
+    /// The account has used up its character quota
+    QuotaExceeded,
+    /// The service is throttling requests
+    RateLimited,
+    /// Any other unsuccessful HTTP status
+    Status(StatusCode),
+    /// The response body was not valid JSON
+    InvalidResponse(String),
+    /// The response JSON did not contain a translation
+    UnexpectedResponse,
+}
+
+impl fmt::Display for TranslationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TranslationError::NotConfigured(message) => write!(f, "{}", message),
+            TranslationError::InvalidConfig(message) => write!(f, "{}", message),
+            TranslationError::Connection(message) => write!(f, "{}", gettext_f("Error: Could not connect to translation service: {}", &[message])),
+            TranslationError::Timeout => write!(f, "{}", gettext("Error: Translation service did not respond in time")),
+            TranslationError::Unauthorized => write!(f, "{}", gettext("Error: API key was rejected")),
+            TranslationError::UnsupportedLanguage(message) => write!(f, "{}", message),
+            TranslationError::QuotaExceeded => write!(f, "{}", gettext("Error: Character quota exceeded")),
+            TranslationError::RateLimited => write!(f, "{}", gettext("Error: Too many requests, try again later")),
+            TranslationError::Status(status) => write!(f, "{}", gettext_f("Error: Server returned status {}", &[&status.to_string()])),
+            TranslationError::InvalidResponse(message) => write!(f, "{}", gettext_f("Error: Could not parse response: {}", &[message])),
+            TranslationError::UnexpectedResponse => write!(f, "{}", gettext("Translation error: Unexpected response format")),
+        }
+    }
+}
+
+impl From<reqwest::Error> for TranslationError {
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            TranslationError::Timeout
+        } else {
+            TranslationError::Connection(error.to_string())
+        }
+    }
+}
+
+impl TranslationError {
+    /// Classify an unsuccessful HTTP status
+    fn from_status(status: StatusCode) -> Self {
+        match status.as_u16() {
+            401 | 403 => TranslationError::Unauthorized,
+            429 => TranslationError::RateLimited,
+            // DeepL reports an exhausted quota with 456
+            456 => TranslationError::QuotaExceeded,
+            _ => TranslationError::Status(status),
         }
     }
 }
@@ -80,38 +376,68 @@ struct TranslationRequest<'a> {
 }
 
 /// Manages translation services and their configurations
+#[derive(Clone)]
 pub struct TranslationManager {
-    client: Client,
+    clients: HashMap<TranslationService, Result<Client, String>>,
     active_service: TranslationService,
     configs: HashMap<TranslationService, ServiceConfig>,
+    pivot_language: String,
+    routing_rules: Vec<RoutingRule>,
+    excluded_services: Vec<TranslationService>,
+    health: HealthTracker,
+    health_ordered_fallback: bool,
 }
 
 impl TranslationManager {
     /// Create a new translation manager with default configuration
     pub fn new() -> Self {
-        // Create a client with default timeouts
-        let client = Client::builder()
-            .timeout(Duration::from_secs(10))
-            .connect_timeout(Duration::from_secs(5))
-            .build()
-            .unwrap_or_else(|_| Client::new());
-        
         // Set up default configurations
         let mut configs = HashMap::new();
         
         configs.insert(TranslationService::GoogleBeta, ServiceConfig::default());
         configs.insert(TranslationService::GoogleOfficial, ServiceConfig::default());
+        configs.insert(TranslationService::GoogleCloudV3, ServiceConfig::default());
         configs.insert(TranslationService::LibreTranslate, ServiceConfig {
             endpoint: Some("https://libretranslate.com/translate".to_string()),
             ..ServiceConfig::default()
         });
         configs.insert(TranslationService::Bing, ServiceConfig::default());
         configs.insert(TranslationService::DeepL, ServiceConfig::default());
+        configs.insert(TranslationService::Lingva, ServiceConfig {
+            endpoint: Some(LINGVA_DEFAULT_INSTANCE.to_string()),
+            ..ServiceConfig::default()
+        });
+        configs.insert(TranslationService::MyMemory, ServiceConfig::default());
+        configs.insert(TranslationService::Pseudo, ServiceConfig::default());
+        
+        // Each service gets its own client built from its configuration
+        let clients = configs.iter()
+            .map(|(service, config)| (service.clone(), config.build_client()))
+            .collect();
         
         TranslationManager {
-            client,
+            clients,
             active_service: TranslationService::GoogleBeta,
             configs,
+            pivot_language: "en".to_string(),
+            routing_rules: Vec::new(),
+            excluded_services: Vec::new(),
+            health: HealthTracker::new(),
+            health_ordered_fallback: false,
+        }
+    }
+    
+    /// Apply the active service, pivot language, routing rules and service configurations from settings
+    pub fn apply_settings(&mut self, settings: &Settings) {
+        self.set_active_service(settings.active_service.clone());
+        self.set_pivot_language(&settings.pivot_language);
+        self.routing_rules = settings.routing_rules.clone();
+        self.health_ordered_fallback = settings.health_ordered_fallback;
+        
+        for service in TranslationService::all_services() {
+            if let Some(config) = settings.get_service_config(&service) {
+                self.update_config(service, config);
+            }
         }
     }
     
@@ -121,14 +447,69 @@ impl TranslationManager {
     }
     
     /// Get the current active service
-    #[allow(dead_code)]
     pub fn get_active_service(&self) -> TranslationService {
         self.active_service.clone()
     }
     
-    /// Update configuration for a service
-    #[allow(dead_code)]
+    /// Success rate and latency of each service from recent requests
+    pub fn health(&self) -> &HealthTracker {
+        &self.health
+    }
+    
+    /// Keep requests away from these services, e.g. when their budget is used up
+    pub fn exclude_services(&mut self, services: Vec<TranslationService>) {
+        self.excluded_services = services;
+    }
+    
+    /// Check whether a service has the credentials it needs and is not excluded
+    fn is_available(&self, service: &TranslationService) -> bool {
+        let configured = self.configs.get(service)
+            .map_or(false, |config| service.is_configured(config));
+        
+        configured && !self.excluded_services.contains(service)
+    }
+    
+    /// Pick the service for a request: the first matching routing rule whose
+    /// service is available, otherwise the active service or the next available
+    /// online one. The offline backend is never picked in place of a real service.
+    pub fn select_service(&self, text: &str, source_lang: &str, target_lang: &str) -> (TranslationService, Option<&RoutingRule>) {
+        // Rules for specific languages can match "auto" text through a local guess
+        let source_lang = detection::resolve_auto(text, source_lang);
+        
+        if let Some(rule) = self.routing_rules.iter()
+            .find(|rule| rule.matches(source_lang, target_lang, text) && self.is_available(&rule.service)) {
+            return (rule.service.clone(), Some(rule));
+        }
+        
+        if self.is_available(&self.active_service) {
+            return (self.active_service.clone(), None);
+        }
+        
+        let services = TranslationService::all_services();
+        let start = services.iter().position(|service| *service == self.active_service).unwrap_or(0);
+        let next = services.iter()
+            .cycle()
+            .skip(start + 1)
+            .take(services.len() - 1)
+            .find(|service| !service.is_offline() && self.is_available(service))
+            .cloned();
+        
+        (next.unwrap_or_else(|| self.active_service.clone()), None)
+    }
+    
+    /// Set the language used as an intermediate step for unsupported pairs
+    pub fn set_pivot_language(&mut self, language: &str) {
+        self.pivot_language = language.to_string();
+    }
+    
+    /// Update configuration for a service and rebuild its HTTP client
     pub fn update_config(&mut self, service: TranslationService, config: ServiceConfig) {
+        let client = config.build_client();
+        if let Err(e) = &client {
+            eprintln!("{}: {}", service, e);
+        }
+        
+        self.clients.insert(service.clone(), client);
         self.configs.insert(service, config);
     }
     
@@ -146,91 +527,206 @@ impl TranslationManager {
     }
     
     /// Translate text using the active service
-    pub async fn translate(&self, text: &str, source_lang: &str, target_lang: &str) -> Result<String, String> {
+    pub async fn translate(&self, text: &str, source_lang: &str, target_lang: &str) -> Result<TranslationResult, TranslationError> {
         if text.is_empty() {
-            return Ok("Please enter text to translate".to_string());
+            return Ok(TranslationResult::direct(gettext("Please enter text to translate")));
         }
         
-        let config = self.get_config(&self.active_service);
-        let request = TranslationRequest {
-            text,
-            source_lang,
-            target_lang,
-            config: &config,
-            client: &self.client,
-        };
+        let (service, rule) = self.select_service(text, source_lang, target_lang);
+        let result = self.translate_via(&service, text, source_lang, target_lang).await?;
         
-        match self.active_service {
-            TranslationService::GoogleBeta => self.translate_google_beta(&request).await,
-            TranslationService::GoogleOfficial => self.translate_google_official(&request).await,
-            TranslationService::LibreTranslate => self.translate_libre(&request).await,
-            TranslationService::Bing => self.translate_bing(&request).await,
-            TranslationService::DeepL => self.translate_deepl(&request).await,
-        }
+        Ok(TranslationResult {
+            routing_rule: rule.cloned(),
+            ..result
+        })
     }
     
-    /// Fall back to another service if the primary one fails
-    pub async fn translate_with_fallback(&self, text: &str, source_lang: &str, target_lang: &str) -> Result<String, String> {
-        // Try the active service first
+    /// Fall back to another service if the primary one fails, returning the
+    /// primary service's error when every fallback fails too
+    pub async fn translate_with_fallback(&self, text: &str, source_lang: &str, target_lang: &str) -> Result<TranslationResult, TranslationError> {
+        // Try the selected service first
         let result = self.translate(text, source_lang, target_lang).await;
         
         if result.is_ok() {
             return result;
         }
         
-        // On failure, try Google Beta as a fallback (if it's not already the active service)
-        if self.active_service != TranslationService::GoogleBeta {
+        let failed_service = self.select_service(text, source_lang, target_lang).0;
+        
+        // Optionally try every other available service, healthiest first
+        if self.health_ordered_fallback {
+            let mut fallbacks: Vec<TranslationService> = TranslationService::all_services()
+                .into_iter()
+                .filter(|service| *service != failed_service && !service.is_offline() && self.is_available(service))
+                .collect();
+            self.health.sort_by_health(&mut fallbacks);
+            
+            for service in fallbacks {
+                println!("Primary translation service failed, falling back to {}", service);
+                if let Ok(translation) = self.translate_via(&service, text, source_lang, target_lang).await {
+                    return Ok(translation);
+                }
+            }
+            
+            return result;
+        }
+        
+        // On failure, try Google Beta as a fallback (if it's not already the selected service)
+        if failed_service != TranslationService::GoogleBeta {
             println!("Primary translation service failed, falling back to Google Beta");
-            let config = self.get_config(&TranslationService::GoogleBeta);
-            let request = TranslationRequest {
-                text,
-                source_lang,
-                target_lang,
-                config: &config,
-                client: &self.client,
-            };
-            return self.translate_google_beta(&request).await;
+            if let Ok(translation) = self.translate_via(&TranslationService::GoogleBeta, text, source_lang, target_lang).await {
+                return Ok(translation);
+            }
         }
         
-        // If Google Beta is already the active service and it failed, return the error
         result
     }
     
+    /// Check a service configuration that may not be saved yet by sending
+    /// a one-word translation, returning how long the request took
+    pub async fn test_service(service: TranslationService, config: ServiceConfig) -> Result<Duration, TranslationError> {
+        let mut manager = TranslationManager::new();
+        manager.update_config(service.clone(), config);
+        
+        let started = Instant::now();
+        manager.translate_direct(&service, "Hello", "en", "de").await?;
+        
+        Ok(started.elapsed())
+    }
+    
+    /// Translate with a specific service and fill in any transliteration
+    /// the service did not provide
+    async fn translate_via(&self, service: &TranslationService, text: &str, source_lang: &str, target_lang: &str) -> Result<TranslationResult, TranslationError> {
+        let source_lang = if service.needs_source_language(&self.get_config(service)) {
+            detection::resolve_auto(text, source_lang)
+        } else {
+            source_lang
+        };
+        
+        let mut result = self.translate_with_pivot(service, text, source_lang, target_lang).await?;
+        
+        if result.source_transliteration.is_none() {
+            result.source_transliteration = transliterate(text);
+        }
+        if result.transliteration.is_none() {
+            result.transliteration = transliterate(&result.text);
+        }
+        
+        Ok(result)
+    }
+    
+    /// Translate with a specific service, going through the pivot language
+    /// when the service cannot handle the pair directly
+    async fn translate_with_pivot(&self, service: &TranslationService, text: &str, source_lang: &str, target_lang: &str) -> Result<TranslationResult, TranslationError> {
+        let config = self.get_config(service);
+        let pivot = self.pivot_language.as_str();
+        let can_pivot = pivot != source_lang
+            && pivot != target_lang
+            && service.supports_pair(&config, source_lang, pivot)
+            && service.supports_pair(&config, pivot, target_lang);
+        
+        // Unsupported pairs without a usable pivot are still attempted directly
+        // so the service can report its own error
+        if service.supports_pair(&config, source_lang, target_lang) || !can_pivot {
+            return self.translate_direct(service, text, source_lang, target_lang).await;
+        }
+        
+        println!("{} does not support {} → {}, pivoting through {}", service, source_lang, target_lang, pivot);
+        let first_leg = self.translate_direct(service, text, source_lang, pivot).await?;
+        let translation = self.translate_direct(service, &first_leg.text, pivot, target_lang).await?;
+        
+        Ok(TranslationResult {
+            billed_characters: first_leg.billed_characters + translation.billed_characters,
+            pivot: Some(PivotStep {
+                language: pivot.to_string(),
+                text: first_leg.text,
+            }),
+            ..translation
+        })
+    }
+    
+    /// Send a single request to a service without any pivoting
+    async fn translate_direct(&self, service: &TranslationService, text: &str, source_lang: &str, target_lang: &str) -> Result<TranslationResult, TranslationError> {
+        let config = self.get_config(service);
+        let client = match self.clients.get(service) {
+            Some(Ok(client)) => client.clone(),
+            Some(Err(e)) => return Err(TranslationError::InvalidConfig(format!("{} is misconfigured: {}", service, e))),
+            None => config.build_client().map_err(TranslationError::InvalidConfig)?,
+        };
+        let request = TranslationRequest {
+            text,
+            source_lang,
+            target_lang,
+            config: &config,
+            client: &client,
+        };
+        
+        let started = Instant::now();
+        let result = match service {
+            TranslationService::GoogleBeta => self.translate_google_beta(&request).await,
+            TranslationService::GoogleOfficial => self.translate_google_official(&request).await.map(TranslationResult::direct),
+            TranslationService::GoogleCloudV3 => self.translate_google_cloud_v3(&request).await.map(TranslationResult::direct),
+            TranslationService::LibreTranslate => self.translate_libre(&request).await.map(TranslationResult::direct),
+            TranslationService::Bing => self.translate_bing(&request).await,
+            TranslationService::DeepL => self.translate_deepl(&request).await.map(TranslationResult::direct),
+            TranslationService::Lingva => self.translate_lingva(&request).await,
+            TranslationService::MyMemory => self.translate_mymemory(&request).await.map(TranslationResult::direct),
+            TranslationService::Pseudo => self.translate_pseudo(&request).await.map(TranslationResult::direct),
+        };
+        
+        // Missing settings say nothing about the health of the service itself
+        if !matches!(result, Err(TranslationError::NotConfigured(_))) {
+            self.health.record(service, result.is_ok(), started.elapsed());
+        }
+        
+        // Services bill by the number of characters in the source text
+        result.map(|translation| TranslationResult {
+            service: Some(service.clone()),
+            billed_characters: text.chars().count(),
+            ..translation
+        })
+    }
+    
     // IMPLEMENTATION OF TRANSLATION SERVICES
     
     /// Helper function to process HTTP responses
-    async fn process_response(&self, response: reqwest::Response) -> Result<serde_json::Value, String> {
+    async fn process_response(&self, response: reqwest::Response) -> Result<serde_json::Value, TranslationError> {
         if !response.status().is_success() {
-            return Err(format!("Error: Server returned status {}", response.status()));
+            return Err(TranslationError::from_status(response.status()));
         }
         
         match response.json().await {
             Ok(json) => Ok(json),
-            Err(e) => Err(format!("Error: Could not parse response: {}", e)),
+            Err(e) => Err(TranslationError::InvalidResponse(e.to_string())),
         }
     }
     
     /// Google Translate (Beta/Free) implementation
-    async fn translate_google_beta(&self, request: &TranslationRequest<'_>) -> Result<String, String> {
+    async fn translate_google_beta(&self, request: &TranslationRequest<'_>) -> Result<TranslationResult, TranslationError> {
         // Properly URL encode the text
         let encoded_text = encode(request.text);
         
+        // Single words and short phrases also ask for alternatives, dictionary
+        // entries, definitions, synonyms and examples
+        let wants_dictionary = is_dictionary_query(request.text);
+        let data_types = if wants_dictionary {
+            "dt=t&dt=rm&dt=at&dt=bd&dt=md&dt=ss&dt=ex"
+        } else {
+            "dt=t&dt=rm"
+        };
+        
         // Format the URL
         let url = format!(
-            "https://translate.googleapis.com/translate_a/single?client=gtx&sl={}&tl={}&dt=t&q={}",
-            request.source_lang, request.target_lang, encoded_text
+            "https://translate.googleapis.com/translate_a/single?client=gtx&sl={}&tl={}&{}&q={}",
+            request.source_lang, request.target_lang, data_types, encoded_text
         );
         
-        // Get the timeout from config
-        let timeout = Duration::from_secs(request.config.timeout_seconds.unwrap_or(10));
-        
-        // Make the request
+        // Make the request (the client applies the configured timeout)
         let response = match request.client.get(&url)
-            .timeout(timeout)
             .send()
             .await {
             Ok(resp) => resp,
-            Err(e) => return Err(format!("Error: Could not connect to translation service: {}", e)),
+            Err(e) => return Err(TranslationError::from(e)),
         };
         
         // Parse the response
@@ -238,6 +734,8 @@ impl TranslationManager {
         
         // Build the complete translation from all segments
         let mut full_translation = String::new();
+        let mut transliteration = None;
+        let mut source_transliteration = None;
         
         // The response structure is an array of arrays, where the first array contains
         // all the translation segments
@@ -245,21 +743,38 @@ impl TranslationManager {
             for segment in segments {
                 if let Some(text) = segment[0].as_str() {
                     full_translation.push_str(text);
+                } else {
+                    // The romanization (dt=rm) comes as a trailing
+                    // [null, null, translation reading, source reading] segment
+                    transliteration = segment[2].as_str().filter(|t| !t.is_empty()).map(String::from);
+                    source_transliteration = segment[3].as_str().filter(|t| !t.is_empty()).map(String::from);
                 }
             }
         } else {
-            return Err("Translation error: Unexpected response format".to_string());
+            return Err(TranslationError::UnexpectedResponse);
         }
         
-        Ok(full_translation)
+        let dictionary = if wants_dictionary {
+            DictionaryResult::from_google_response(&json, &full_translation)
+        } else {
+            None
+        };
+        
+        Ok(TranslationResult {
+            text: full_translation,
+            dictionary,
+            source_transliteration,
+            transliteration,
+            ..Default::default()
+        })
     }
     
     /// Google Translate (Official API) implementation
-    async fn translate_google_official(&self, request: &TranslationRequest<'_>) -> Result<String, String> {
+    async fn translate_google_official(&self, request: &TranslationRequest<'_>) -> Result<String, TranslationError> {
         // Get API key from config
         let api_key = match &request.config.api_key {
             Some(key) => key,
-            None => return Err("Google Translate API key not configured".to_string()),
+            None => return Err(TranslationError::NotConfigured("Google Translate API key not configured".to_string())),
         };
         
         // Properly URL encode the text
@@ -274,7 +789,7 @@ impl TranslationManager {
         // Make the request
         let response = match request.client.get(&url).send().await {
             Ok(resp) => resp,
-            Err(e) => return Err(format!("Error: Could not connect to translation service: {}", e)),
+            Err(e) => return Err(TranslationError::from(e)),
         };
         
         // Parse the response
@@ -291,22 +806,99 @@ impl TranslationManager {
             }
         }
         
-        Err("Translation error: Unexpected response format".to_string())
+        Err(TranslationError::UnexpectedResponse)
+    }
+    
+    /// Google Cloud Translation v3 implementation, authenticated with a service account
+    async fn translate_google_cloud_v3(&self, request: &TranslationRequest<'_>) -> Result<String, TranslationError> {
+        let config = request.config;
+        let credentials_path = match config.credentials_path.as_deref().filter(|path| !path.is_empty()) {
+            Some(path) => path,
+            None => return Err(TranslationError::NotConfigured("Google Cloud service account file not configured".to_string())),
+        };
+        
+        // The project defaults to the one the service account belongs to
+        let project_id = match config.project_id.clone().filter(|id| !id.is_empty()) {
+            Some(id) => id,
+            None => ServiceAccountKey::load(credentials_path)?
+                .project_id
+                .ok_or_else(|| TranslationError::NotConfigured("Google Cloud project ID not configured".to_string()))?,
+        };
+        let location = config.location.as_deref().filter(|l| !l.is_empty()).unwrap_or("global");
+        let parent = format!("projects/{}/locations/{}", project_id, location);
+        
+        let token = google_auth::access_token(request.client, credentials_path).await?;
+        
+        // The text goes in the request body rather than the URL
+        let mut body = serde_json::json!({
+            "contents": [request.text],
+            "targetLanguageCode": request.target_lang,
+            "mimeType": "text/plain",
+        });
+        
+        if request.source_lang != "auto" {
+            body["sourceLanguageCode"] = serde_json::Value::String(request.source_lang.to_string());
+        }
+        
+        // Short model names like "general/nmt" are expanded to a full resource name
+        if let Some(model) = config.model.as_deref().filter(|m| !m.is_empty()) {
+            let model = if model.starts_with("projects/") {
+                model.to_string()
+            } else {
+                format!("{}/models/{}", parent, model)
+            };
+            body["model"] = serde_json::Value::String(model);
+        }
+        
+        let glossary = config.glossary.as_deref().filter(|g| !g.is_empty());
+        if let Some(glossary) = glossary {
+            let glossary = if glossary.starts_with("projects/") {
+                glossary.to_string()
+            } else {
+                format!("{}/glossaries/{}", parent, glossary)
+            };
+            body["glossaryConfig"] = serde_json::json!({ "glossary": glossary });
+        }
+        
+        let url = format!("https://translation.googleapis.com/v3/{}:translateText", parent);
+        let response = match request.client.post(&url)
+            .bearer_auth(&token)
+            .json(&body)
+            .send()
+            .await {
+            Ok(resp) => resp,
+            Err(e) => return Err(TranslationError::from(e)),
+        };
+        
+        let json = self.process_response(response).await?;
+        
+        // With a glossary, the glossary-aware translation is returned separately
+        let translations = if glossary.is_some() && json.get("glossaryTranslations").is_some() {
+            &json["glossaryTranslations"]
+        } else {
+            &json["translations"]
+        };
+        
+        if let Some(text) = translations[0]["translatedText"].as_str() {
+            return Ok(text.to_string());
+        }
+        
+        Err(TranslationError::UnexpectedResponse)
     }
     
     /// LibreTranslate implementation
-    async fn translate_libre(&self, request: &TranslationRequest<'_>) -> Result<String, String> {
+    async fn translate_libre(&self, request: &TranslationRequest<'_>) -> Result<String, TranslationError> {
         // Get API endpoint from config
         let endpoint = match &request.config.endpoint {
             Some(ep) => ep,
-            None => return Err("LibreTranslate API endpoint not configured".to_string()),
+            None => return Err(TranslationError::NotConfigured("LibreTranslate API endpoint not configured".to_string())),
         };
         
         // Prepare request body
         let mut request_body = serde_json::json!({
             "q": request.text,
-            "source": request.source_lang,
-            "target": request.target_lang,
+            "source": languages::service_code(request.source_lang, &TranslationService::LibreTranslate),
+            "target": languages::service_code(request.target_lang, &TranslationService::LibreTranslate),
         });
         
         // Add API key if present
@@ -320,7 +912,7 @@ impl TranslationManager {
             .send()
             .await {
             Ok(resp) => resp,
-            Err(e) => return Err(format!("Error: Could not connect to translation service: {}", e)),
+            Err(e) => return Err(TranslationError::from(e)),
         };
         
         // Parse the response
@@ -333,99 +925,243 @@ impl TranslationManager {
             }
         }
         
-        Err("Translation error: Unexpected response format".to_string())
+        Err(TranslationError::UnexpectedResponse)
     }
     
     /// Bing Translator implementation
-    async fn translate_bing(&self, request: &TranslationRequest<'_>) -> Result<String, String> {
+    async fn translate_bing(&self, request: &TranslationRequest<'_>) -> Result<TranslationResult, TranslationError> {
+        let source_lang = languages::service_code(request.source_lang, &TranslationService::Bing);
+        let target_lang = languages::service_code(request.target_lang, &TranslationService::Bing);
+        
+        // Azure detects the language itself when no source is given
+        let mut query_params = vec![("api-version", "3.0"), ("to", target_lang)];
+        if request.source_lang != "auto" {
+            query_params.push(("from", source_lang));
+        }
+        
+        let body = serde_json::json!([{"text": request.text}]);
+        let json = self.azure_request(request, "translate", &query_params, body).await?;
+        
+        let text = match json[0]["translations"][0]["text"].as_str() {
+            Some(text) => text.to_string(),
+            None => return Err(TranslationError::UnexpectedResponse),
+        };
+        
+        // Single words also get dictionary translations and usage examples
+        let detected_lang = json[0]["detectedLanguage"]["language"].as_str();
+        let dictionary = match (is_dictionary_query(request.text), request.source_lang) {
+            (false, _) => None,
+            (true, "auto") => match detected_lang {
+                Some(detected) => self.azure_dictionary(request, detected, target_lang).await,
+                None => None,
+            },
+            (true, _) => self.azure_dictionary(request, source_lang, target_lang).await,
+        };
+        
+        Ok(TranslationResult {
+            text,
+            dictionary,
+            ..Default::default()
+        })
+    }
+    
+    /// Look up dictionary translations and examples for a single word.
+    ///
+    /// Azure only has dictionaries for pairs with English, so any failure just
+    /// leaves out the dictionary data.
+    async fn azure_dictionary(&self, request: &TranslationRequest<'_>, source_lang: &str, target_lang: &str) -> Option<DictionaryResult> {
+        let query_params = [("api-version", "3.0"), ("from", source_lang), ("to", target_lang)];
+        let word = request.text.trim();
+        
+        let lookup_body = serde_json::json!([{"Text": word}]);
+        let lookup = self.azure_request(request, "dictionary/lookup", &query_params, lookup_body).await.ok()?;
+        let mut dictionary = DictionaryResult::from_azure_lookup(&lookup)?;
+        
+        // Examples for the most likely translations
+        let example_pairs: Vec<serde_json::Value> = lookup[0]["translations"].as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|translation| translation["normalizedTarget"].as_str())
+            .take(MAX_AZURE_EXAMPLE_TRANSLATIONS)
+            .map(|translation| serde_json::json!({"Text": word, "Translation": translation}))
+            .collect();
+        
+        if !example_pairs.is_empty() {
+            let examples_body = serde_json::Value::Array(example_pairs);
+            if let Ok(examples) = self.azure_request(request, "dictionary/examples", &query_params, examples_body).await {
+                dictionary.add_azure_examples(&examples);
+            }
+        }
+        
+        Some(dictionary)
+    }
+    
+    /// Send a request to an Azure Translator endpoint with the key and region headers
+    async fn azure_request(
+        &self,
+        request: &TranslationRequest<'_>,
+        path: &str,
+        query_params: &[(&str, &str)],
+        body: serde_json::Value,
+    ) -> Result<serde_json::Value, TranslationError> {
         // Get API key from config
         let api_key = match &request.config.api_key {
             Some(key) => key,
-            None => return Err("Bing Translator API key not configured".to_string()),
+            None => return Err(TranslationError::NotConfigured("Bing Translator API key not configured".to_string())),
         };
         
-        // Prepare the endpoint
-        let endpoint = "https://api.cognitive.microsofttranslator.com/translate";
+        // A custom endpoint replaces the global one, e.g. for a regional resource
+        let base = request.config.endpoint.as_deref()
+            .filter(|endpoint| !endpoint.is_empty())
+            .unwrap_or(AZURE_TRANSLATOR_ENDPOINT)
+            .trim_end_matches('/');
         
         // Prepare the request
-        let mut request_builder = request.client.post(endpoint)
+        let mut request_builder = request.client.post(format!("{}/{}", base, path))
             .header("Ocp-Apim-Subscription-Key", api_key)
-            .header("Content-Type", "application/json");
+            .header("Content-Type", "application/json")
+            .query(query_params);
         
-        // Add region if provided
-        if let Some(region) = &request.config.endpoint {
+        // Add region if provided (needed for regional and multi-service resources)
+        if let Some(region) = request.config.region.as_deref().filter(|region| !region.is_empty()) {
             request_builder = request_builder.header("Ocp-Apim-Subscription-Region", region);
         }
         
-        // Add query parameters
-        let query_params = [
-            ("api-version", "3.0"),
-            ("from", request.source_lang),
-            ("to", request.target_lang),
+        // Make the request
+        let response = match request_builder
+            .json(&body)
+            .send()
+            .await {
+            Ok(resp) => resp,
+            Err(e) => return Err(TranslationError::from(e)),
+        };
+        
+        self.process_response(response).await
+    }
+    
+    /// Lingva Translate implementation (any public or self-hosted instance)
+    async fn translate_lingva(&self, request: &TranslationRequest<'_>) -> Result<TranslationResult, TranslationError> {
+        let instance = request.config.endpoint.as_deref()
+            .filter(|endpoint| !endpoint.is_empty())
+            .unwrap_or(LINGVA_DEFAULT_INSTANCE)
+            .trim_end_matches('/');
+        
+        // Lingva takes everything in the path
+        let url = format!(
+            "{}/api/v1/{}/{}/{}",
+            instance,
+            languages::service_code(request.source_lang, &TranslationService::Lingva),
+            languages::service_code(request.target_lang, &TranslationService::Lingva),
+            encode(request.text)
+        );
+        
+        let response = match request.client.get(&url).send().await {
+            Ok(resp) => resp,
+            Err(e) => return Err(TranslationError::from(e)),
+        };
+        
+        let json = self.process_response(response).await?;
+        
+        let text = match json["translation"].as_str() {
+            Some(text) => text.to_string(),
+            None => return Err(TranslationError::UnexpectedResponse),
+        };
+        
+        // Lingva passes on Google's romanization
+        let pronunciation = |key: &str| {
+            json["info"]["pronunciation"][key].as_str()
+                .filter(|reading| !reading.is_empty())
+                .map(String::from)
+        };
+        
+        Ok(TranslationResult {
+            text,
+            source_transliteration: pronunciation("query"),
+            transliteration: pronunciation("translation"),
+            ..Default::default()
+        })
+    }
+    
+    /// Offline pseudo-localization implementation, with simulated latency and failures
+    async fn translate_pseudo(&self, request: &TranslationRequest<'_>) -> Result<String, TranslationError> {
+        let options = &request.config.pseudo;
+        options.simulate_request().await?;
+        
+        Ok(options.translate(request.text, request.target_lang))
+    }
+    
+    /// MyMemory implementation
+    async fn translate_mymemory(&self, request: &TranslationRequest<'_>) -> Result<String, TranslationError> {
+        if request.source_lang == "auto" {
+            return Err(TranslationError::UnsupportedLanguage("MyMemory needs a source language and the language of the text could not be guessed".to_string()));
+        }
+        
+        let mut query_params = vec![
+            ("q", request.text.to_string()),
+            ("langpair", format!("{}|{}", request.source_lang, request.target_lang)),
         ];
         
-        request_builder = request_builder.query(&query_params);
+        // An email raises the anonymous daily quota
+        if let Some(email) = request.config.email.as_deref().filter(|email| !email.is_empty()) {
+            query_params.push(("de", email.to_string()));
+        }
         
-        // Prepare body
-        let body = serde_json::json!([{"text": request.text}]);
+        // A key gives access to a private translation memory
+        if let Some(api_key) = request.config.api_key.as_deref().filter(|key| !key.is_empty()) {
+            query_params.push(("key", api_key.to_string()));
+        }
         
-        // Make the request
-        let response = match request_builder
-            .json(&body)
+        let response = match request.client.get(MYMEMORY_ENDPOINT)
+            .query(&query_params)
             .send()
             .await {
             Ok(resp) => resp,
-            Err(e) => return Err(format!("Error: Could not connect to translation service: {}", e)),
+            Err(e) => return Err(TranslationError::from(e)),
         };
         
-        // Parse the response
         let json = self.process_response(response).await?;
         
-        // Extract the translation
-        if let Some(translations) = json[0].get("translations") {
-            if let Some(translation) = translations[0].get("text") {
-                if let Some(text) = translation.as_str() {
-                    return Ok(text.to_string());
-                }
-            }
+        // Errors are reported in the body with a successful HTTP status,
+        // and the status may be a number or a string
+        if json["quotaFinished"].as_bool() == Some(true) {
+            return Err(TranslationError::QuotaExceeded);
+        }
+        let status = json["responseStatus"].as_u64()
+            .or_else(|| json["responseStatus"].as_str().and_then(|status| status.parse().ok()));
+        if let Some(status) = status.filter(|status| *status != 200) {
+            let details = json["responseDetails"].as_str().unwrap_or_default();
+            return Err(match status {
+                403 if details.contains("LANGUAGE") => TranslationError::UnsupportedLanguage(details.to_string()),
+                429 => TranslationError::RateLimited,
+                _ => TranslationError::InvalidResponse(format!("{} {}", status, details)),
+            });
         }
         
-        Err("Translation error: Unexpected response format".to_string())
+        match json["responseData"]["translatedText"].as_str() {
+            Some(text) => Ok(text.to_string()),
+            None => Err(TranslationError::UnexpectedResponse),
+        }
     }
     
     /// DeepL implementation
-    async fn translate_deepl(&self, request: &TranslationRequest<'_>) -> Result<String, String> {
+    async fn translate_deepl(&self, request: &TranslationRequest<'_>) -> Result<String, TranslationError> {
         // Get API key from config
         let api_key = match &request.config.api_key {
             Some(key) => key,
-            None => return Err("DeepL API key not configured".to_string()),
+            None => return Err(TranslationError::NotConfigured("DeepL API key not configured".to_string())),
         };
         
         // Determine endpoint based on API key type (free vs pro)
-        let is_free_api = api_key.ends_with(":fx");
-        let endpoint = if is_free_api {
-            "https://api-free.deepl.com/v2/translate"
-        } else {
-            "https://api.deepl.com/v2/translate"
-        };
-        
-        // Map language codes (DeepL uses different codes for some languages)
-        let source_lang_mapped = match request.source_lang {
-            "auto" => "auto".to_string(),
-            "en" => "EN".to_string(),
-            "zh-CN" => "ZH".to_string(),
-            "ja" => "JA".to_string(),
-            // Add more mappings as needed
-            _ => request.source_lang.to_uppercase(),
-        };
+        let endpoint = format!("{}/translate", deepl_api_base(api_key));
         
-        let target_lang_mapped = match request.target_lang {
-            "en" => "EN-US".to_string(), // DeepL distinguishes between EN-US and EN-GB
-            "zh-CN" => "ZH".to_string(),
-            "pt" => "PT-BR".to_string(), // DeepL distinguishes between PT-PT and PT-BR
-            // Add more mappings as needed
-            _ => request.target_lang.to_uppercase(),
-        };
+        // Target codes may name a variant such as EN-US or PT-BR, while
+        // source codes are only the base language
+        let target_lang_mapped = languages::service_code(request.target_lang, &TranslationService::DeepL).to_uppercase();
+        let source_lang_mapped = languages::service_code(request.source_lang, &TranslationService::DeepL)
+            .split('-')
+            .next()
+            .unwrap_or_default()
+            .to_uppercase();
         
         // Prepare request body
         let mut params = vec![
@@ -439,13 +1175,13 @@ impl TranslationManager {
         }
         
         // Make the request
-        let response = match request.client.post(endpoint)
+        let response = match request.client.post(&endpoint)
             .header("Authorization", format!("DeepL-Auth-Key {}", api_key))
             .form(&params)
             .send()
             .await {
             Ok(resp) => resp,
-            Err(e) => return Err(format!("Error: Could not connect to translation service: {}", e)),
+            Err(e) => return Err(TranslationError::from(e)),
         };
         
         // Parse the response
@@ -460,6 +1196,169 @@ impl TranslationManager {
             }
         }
         
-        Err("Translation error: Unexpected response format".to_string())
+        Err(TranslationError::UnexpectedResponse)
+    }
+}
+
+/// Base URL of the DeepL API; free keys end in ":fx" and use a separate host
+pub fn deepl_api_base(api_key: &str) -> &'static str {
+    if api_key.ends_with(":fx") {
+        "https://api-free.deepl.com/v2"
+    } else {
+        "https://api.deepl.com/v2"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pseudo::{pseudo_localize, PseudoMode, PseudoOptions};
+    
+    /// Manager with the offline backend active and the given options
+    fn pseudo_manager(pseudo: PseudoOptions) -> TranslationManager {
+        let mut manager = TranslationManager::new();
+        manager.set_active_service(TranslationService::Pseudo);
+        manager.update_config(TranslationService::Pseudo, ServiceConfig {
+            pseudo,
+            ..ServiceConfig::default()
+        });
+        manager
     }
-}
\ No newline at end of file
+    
+    /// Owned language pairs for `PseudoOptions`
+    fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter()
+            .map(|(source, target)| (source.to_string(), target.to_string()))
+            .collect()
+    }
+    
+    /// Every service except the given ones
+    fn all_services_except(kept: &[TranslationService]) -> Vec<TranslationService> {
+        TranslationService::all_services()
+            .into_iter()
+            .filter(|service| !kept.contains(service))
+            .collect()
+    }
+    
+    #[tokio::test]
+    async fn pseudo_translates_offline() {
+        let manager = pseudo_manager(PseudoOptions::default());
+        
+        let result = manager.translate("Hello world", "en", "de").await.unwrap();
+        
+        assert_eq!(result.text, pseudo_localize("Hello world", 30));
+        assert_eq!(result.service, Some(TranslationService::Pseudo));
+        assert_eq!(result.billed_characters, 11);
+        assert!(result.pivot.is_none());
+    }
+    
+    #[tokio::test]
+    async fn injected_errors_are_recorded() {
+        let manager = pseudo_manager(PseudoOptions {
+            error_percent: 100,
+            ..PseudoOptions::default()
+        });
+        
+        for _ in 0..3 {
+            assert!(manager.translate("Hello", "en", "de").await.is_err());
+        }
+        
+        let health = manager.health().health(&TranslationService::Pseudo);
+        assert_eq!(health.requests, 3);
+        assert_eq!(health.success_rate, 0.0);
+    }
+    
+    #[tokio::test]
+    async fn fallback_tries_other_services_on_injected_errors() {
+        let mut manager = pseudo_manager(PseudoOptions {
+            error_percent: 100,
+            ..PseudoOptions::default()
+        });
+        manager.health_ordered_fallback = true;
+        
+        // Nothing listens on the discard port, so the fallback fails without the network
+        manager.update_config(TranslationService::Lingva, ServiceConfig {
+            endpoint: Some("http://127.0.0.1:9".to_string()),
+            timeout_seconds: Some(1),
+            ..ServiceConfig::default()
+        });
+        manager.exclude_services(all_services_except(&[TranslationService::Pseudo, TranslationService::Lingva]));
+        
+        let result = manager.translate_with_fallback("Hello", "en", "de").await;
+        
+        // The error is the primary service's, not the unreachable fallback's
+        let error = result.unwrap_err().to_string();
+        assert!(!error.contains("127.0.0.1"), "{}", error);
+        assert_eq!(manager.health().health(&TranslationService::Pseudo).requests, 1);
+        assert_eq!(manager.health().health(&TranslationService::Lingva).requests, 1);
+    }
+    
+    #[tokio::test]
+    async fn fallback_never_uses_pseudo() {
+        let mut manager = TranslationManager::new();
+        manager.set_active_service(TranslationService::Lingva);
+        manager.health_ordered_fallback = true;
+        manager.update_config(TranslationService::Lingva, ServiceConfig {
+            endpoint: Some("http://127.0.0.1:9".to_string()),
+            timeout_seconds: Some(1),
+            ..ServiceConfig::default()
+        });
+        manager.exclude_services(all_services_except(&[TranslationService::Pseudo, TranslationService::Lingva]));
+        
+        let result = manager.translate_with_fallback("Hello", "en", "de").await;
+        
+        assert!(result.is_err());
+        assert_eq!(manager.health().health(&TranslationService::Pseudo).requests, 0);
+    }
+    
+    #[test]
+    fn excluded_active_service_does_not_fall_back_to_pseudo() {
+        let mut manager = TranslationManager::new();
+        manager.set_active_service(TranslationService::MyMemory);
+        manager.exclude_services(vec![TranslationService::MyMemory]);
+        
+        let (service, rule) = manager.select_service("Hello", "en", "de");
+        assert_eq!(service, TranslationService::GoogleBeta);
+        assert!(rule.is_none());
+        
+        // With no real service left the active one is kept
+        manager.exclude_services(all_services_except(&[TranslationService::Pseudo]));
+        let (service, _) = manager.select_service("Hello", "en", "de");
+        assert_eq!(service, TranslationService::MyMemory);
+    }
+    
+    #[tokio::test]
+    async fn unsupported_pair_pivots_through_english() {
+        let manager = pseudo_manager(PseudoOptions {
+            mode: PseudoMode::Echo,
+            pairs: pairs(&[("uk", "en"), ("en", "de"), ("en", "uk")]),
+            ..PseudoOptions::default()
+        });
+        
+        // Supported pairs are translated directly
+        let direct = manager.translate("Hello", "en", "uk").await.unwrap();
+        assert_eq!(direct.text, "[uk] Hello");
+        assert!(direct.pivot.is_none());
+        
+        let result = manager.translate("Привіт", "uk", "de").await.unwrap();
+        let pivot = result.pivot.unwrap();
+        assert_eq!(pivot.language, "en");
+        assert_eq!(pivot.text, "[en] Привіт");
+        assert_eq!(result.text, "[de] [en] Привіт");
+        assert_eq!(result.billed_characters, "Привіт".chars().count() + "[en] Привіт".chars().count());
+    }
+    
+    #[tokio::test]
+    async fn pair_without_usable_pivot_is_sent_directly() {
+        let mut manager = pseudo_manager(PseudoOptions {
+            mode: PseudoMode::Echo,
+            pairs: pairs(&[("uk", "en"), ("en", "de")]),
+            ..PseudoOptions::default()
+        });
+        manager.set_pivot_language("fr");
+        
+        let result = manager.translate("Привіт", "uk", "de").await.unwrap();
+        assert_eq!(result.text, "[de] Привіт");
+        assert!(result.pivot.is_none());
+    }
+}
@@ -0,0 +1,125 @@
+/// Romanize Cyrillic and Greek text with simple character rules.
+///
+/// Used when the translation service does not return a transliteration.
+/// Returns `None` if the text contains no Cyrillic or Greek letters.
+pub fn transliterate(text: &str) -> Option<String> {
+    if !text.chars().any(|c| romanize_lower(to_lower(c)).is_some()) {
+        return None;
+    }
+    
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        let lower = to_lower(c);
+        match romanize_lower(lower) {
+            Some(latin) if lower != c => {
+                // Keep the capitalization of the original letter
+                let mut chars = latin.chars();
+                if let Some(first) = chars.next() {
+                    result.extend(first.to_uppercase());
+                    result.push_str(chars.as_str());
+                }
+            }
+            Some(latin) => result.push_str(latin),
+            None => result.push(c),
+        }
+    }
+    
+    Some(result)
+}
+
+fn to_lower(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+fn romanize_lower(c: char) -> Option<&'static str> {
+    cyrillic(c).or_else(|| greek(c))
+}
+
+/// Cyrillic letters (Russian, Ukrainian, Belarusian, Bulgarian, Serbian, Macedonian)
+fn cyrillic(c: char) -> Option<&'static str> {
+    let latin = match c {
+        'а' => "a",
+        'б' => "b",
+        'в' => "v",
+        'г' => "g",
+        'ґ' => "g",
+        'д' => "d",
+        'ђ' => "đ",
+        'ѓ' => "gj",
+        'е' => "e",
+        'ё' => "yo",
+        'є' => "ye",
+        'ж' => "zh",
+        'з' => "z",
+        'ѕ' => "dz",
+        'и' => "i",
+        'і' => "i",
+        'ї' => "yi",
+        'й' => "y",
+        'ј' => "j",
+        'к' => "k",
+        'л' => "l",
+        'љ' => "lj",
+        'м' => "m",
+        'н' => "n",
+        'њ' => "nj",
+        'о' => "o",
+        'п' => "p",
+        'р' => "r",
+        'с' => "s",
+        'т' => "t",
+        'ћ' => "ć",
+        'ќ' => "kj",
+        'у' => "u",
+        'ў' => "w",
+        'ф' => "f",
+        'х' => "kh",
+        'ц' => "ts",
+        'ч' => "ch",
+        'џ' => "dž",
+        'ш' => "sh",
+        'щ' => "shch",
+        'ъ' => "\"",
+        'ы' => "y",
+        'ь' => "'",
+        'э' => "e",
+        'ю' => "yu",
+        'я' => "ya",
+        _ => return None,
+    };
+    
+    Some(latin)
+}
+
+/// Greek letters, including accented and final forms
+fn greek(c: char) -> Option<&'static str> {
+    let latin = match c {
+        'α' | 'ά' => "a",
+        'β' => "v",
+        'γ' => "g",
+        'δ' => "d",
+        'ε' | 'έ' => "e",
+        'ζ' => "z",
+        'η' | 'ή' => "i",
+        'θ' => "th",
+        'ι' | 'ί' | 'ϊ' | 'ΐ' => "i",
+        'κ' => "k",
+        'λ' => "l",
+        'μ' => "m",
+        'ν' => "n",
+        'ξ' => "x",
+        'ο' | 'ό' => "o",
+        'π' => "p",
+        'ρ' => "r",
+        'σ' | 'ς' => "s",
+        'τ' => "t",
+        'υ' | 'ύ' | 'ϋ' | 'ΰ' => "y",
+        'φ' => "f",
+        'χ' => "ch",
+        'ψ' => "ps",
+        'ω' | 'ώ' => "o",
+        _ => return None,
+    };
+    
+    Some(latin)
+}
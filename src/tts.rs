@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use urlencoding::encode;
+use crate::temp_file::TempFile;
+use crate::translation::ServiceConfig;
+
+/// Longest text the online Google TTS endpoint accepts in a single request
+const ONLINE_TTS_MAX_CHARS: usize = 200;
+
+/// Local speech synthesis engines, invoked as subprocesses
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TtsEngine {
+    EspeakNg,
+    Piper,
+}
+
+impl fmt::Display for TtsEngine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TtsEngine::EspeakNg => write!(f, "eSpeak NG"),
+            TtsEngine::Piper => write!(f, "Piper"),
+        }
+    }
+}
+
+impl TtsEngine {
+    /// Get engine name for configuration lookup
+    pub fn config_name(&self) -> &'static str {
+        match self {
+            TtsEngine::EspeakNg => "EspeakNg",
+            TtsEngine::Piper => "Piper",
+        }
+    }
+    
+    /// Look up an engine by its configuration name
+    pub fn from_config_name(name: &str) -> Option<TtsEngine> {
+        match name {
+            "EspeakNg" => Some(TtsEngine::EspeakNg),
+            "Piper" => Some(TtsEngine::Piper),
+            _ => None,
+        }
+    }
+}
+
+/// Text-to-speech options taken from the settings
+#[derive(Debug, Clone)]
+pub struct TtsOptions {
+    pub engine: TtsEngine,
+    pub voices: HashMap<String, String>,
+    pub online_fallback: bool,
+    /// Proxy, certificates and headers for the online endpoint
+    pub online_config: ServiceConfig,
+}
+
+/// Synthesize speech for the text into a temporary audio file, deleted once dropped.
+///
+/// The local engine is tried first; the online Google TTS endpoint is only
+/// used when the local engine fails and the fallback is enabled.
+pub async fn synthesize(text: &str, lang: &str, options: &TtsOptions) -> Result<TempFile, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("No text to speak".to_string());
+    }
+    
+    let local_error = match synthesize_local(text, lang, options).await {
+        Ok(audio) => return Ok(audio),
+        Err(e) => e,
+    };
+    
+    if !options.online_fallback {
+        return Err(local_error);
+    }
+    
+    println!("{}, falling back to online TTS", local_error);
+    synthesize_online(text, lang, &options.online_config).await
+}
+
+/// Run the configured local engine and write a WAV file
+async fn synthesize_local(text: &str, lang: &str, options: &TtsOptions) -> Result<TempFile, String> {
+    let output = TempFile::new("tts", "wav")?;
+    let voice = options.voices.get(lang).filter(|v| !v.is_empty());
+    
+    let mut command = match options.engine {
+        TtsEngine::EspeakNg => {
+            let mut command = Command::new("espeak-ng");
+            // eSpeak NG understands most language codes as voice names
+            let voice = voice.map(String::as_str).unwrap_or(match lang {
+                "auto" => "en",
+                "zh-CN" => "cmn",
+                "zh-TW" => "yue",
+                other => other,
+            });
+            command.args(["-v", voice, "-w"]).arg(output.path()).arg("--").arg(text);
+            command
+        }
+        TtsEngine::Piper => {
+            // Piper voices are model files, so there is no sensible default
+            let model = voice.ok_or_else(|| format!("No Piper voice configured for '{}'", lang))?;
+            let mut command = Command::new("piper");
+            command.arg("--model").arg(model).arg("--output_file").arg(output.path());
+            command.stdin(Stdio::piped());
+            command
+        }
+    };
+    
+    command.stdout(Stdio::null()).stderr(Stdio::piped());
+    
+    let mut child = command.spawn()
+        .map_err(|e| format!("Could not start {}: {}", options.engine, e))?;
+    
+    // Piper reads the text from standard input
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes()).await
+            .map_err(|e| format!("Could not send text to {}: {}", options.engine, e))?;
+    }
+    
+    let result = child.wait_with_output().await
+        .map_err(|e| format!("{} failed: {}", options.engine, e))?;
+    
+    if !result.status.success() {
+        return Err(format!(
+            "{} failed: {}",
+            options.engine,
+            String::from_utf8_lossy(&result.stderr).trim()
+        ));
+    }
+    
+    Ok(output)
+}
+
+/// Download speech from the Google Translate TTS endpoint as an MP3 file
+async fn synthesize_online(text: &str, lang: &str, config: &ServiceConfig) -> Result<TempFile, String> {
+    if text.chars().count() > ONLINE_TTS_MAX_CHARS {
+        return Err(format!("Online TTS is limited to {} characters", ONLINE_TTS_MAX_CHARS));
+    }
+    
+    let lang = if lang == "auto" { "en" } else { lang };
+    let url = format!(
+        "https://translate.google.com/translate_tts?ie=UTF-8&client=tw-ob&tl={}&q={}",
+        lang, encode(text)
+    );
+    
+    let client = config.build_client()?;
+    let response = match client.get(&url)
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await {
+        Ok(resp) => resp,
+        Err(e) => return Err(format!("Error: Could not connect to TTS service: {}", e)),
+    };
+    
+    if !response.status().is_success() {
+        return Err(format!("Error: TTS service returned status {}", response.status()));
+    }
+    
+    let audio = response.bytes().await
+        .map_err(|e| format!("Error: Could not read TTS audio: {}", e))?;
+    
+    let output = TempFile::new("tts", "mp3")?;
+    tokio::fs::write(output.path(), &audio).await
+        .map_err(|e| format!("Error: Could not save TTS audio: {}", e))?;
+    
+    Ok(output)
+}
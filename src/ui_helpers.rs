@@ -2,13 +2,17 @@ use gtk::prelude::*;
 use gtk::{
     Box as GtkBox,
     Button,
+    ComboBoxText,
     Entry,
     Frame,
     Label,
     Orientation,
+    TextDirection,
     Widget,
 };
 use gtk::glib;
+use crate::database::{MATCH_END, MATCH_START};
+use crate::languages::{self, LANGUAGES};
 
 /// Helper for creating a labeled widget with consistent layout
 #[allow(dead_code)]
@@ -164,4 +168,32 @@ pub fn create_form_field(label_text: &str, placeholder: Option<&str>) -> (GtkBox
     field_box.append(&entry);
     
     (field_box, entry)
-}
\ No newline at end of file
+}
+
+/// Fill a language selector from the language registry, with the language
+/// tags as IDs and optionally "Detect language" first
+pub fn append_languages(combo: &ComboBoxText, include_auto: bool) {
+    if include_auto {
+        combo.append(Some(languages::AUTO), &languages::display_name(languages::AUTO));
+    }
+    
+    for language in LANGUAGES {
+        combo.append(Some(language.tag), &language.label());
+    }
+}
+
+/// Text direction for content in a language
+pub fn text_direction(lang: &str) -> TextDirection {
+    if languages::is_rtl(lang) {
+        TextDirection::Rtl
+    } else {
+        TextDirection::Ltr
+    }
+}
+
+/// Pango markup for a search snippet, with the matched terms highlighted
+pub fn match_markup(snippet: &str) -> String {
+    glib::markup_escape_text(snippet)
+        .replace(MATCH_START, "<span weight=\"bold\" background=\"#fce94f\" foreground=\"#000000\">")
+        .replace(MATCH_END, "</span>")
+}
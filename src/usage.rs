@@ -0,0 +1,81 @@
+use chrono::Local;
+use crate::translation::{deepl_api_base, ServiceConfig};
+
+/// Characters used in the current billing period as reported by a service
+#[derive(Debug, Clone)]
+pub struct ServiceUsage {
+    pub characters: u64,
+    pub limit: Option<u64>,
+}
+
+/// How a service's usage compares to its monthly budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetStatus {
+    Unlimited,
+    WithinBudget,
+    Warning,
+    Exceeded,
+}
+
+impl BudgetStatus {
+    /// Work out the status of `used` characters against an optional budget
+    pub fn of(used: u64, budget: Option<u64>, warning_percent: u8) -> Self {
+        match budget {
+            None => BudgetStatus::Unlimited,
+            Some(budget) if used >= budget => BudgetStatus::Exceeded,
+            Some(budget) if used * 100 >= budget * warning_percent as u64 => BudgetStatus::Warning,
+            Some(_) => BudgetStatus::WithinBudget,
+        }
+    }
+}
+
+/// Key of the current month in usage records, e.g. "2024-05"
+pub fn current_month() -> String {
+    Local::now().format("%Y-%m").to_string()
+}
+
+/// Format a character count with thousands separators
+pub fn format_characters(count: u64) -> String {
+    let digits = count.to_string();
+    let mut result = String::with_capacity(digits.len() + digits.len() / 3);
+    
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            result.push(',');
+        }
+        result.push(digit);
+    }
+    
+    result
+}
+
+/// Ask DeepL how many characters the key has used in the current billing period
+pub async fn fetch_deepl_usage(config: &ServiceConfig) -> Result<ServiceUsage, String> {
+    let api_key = config.api_key.as_deref()
+        .filter(|key| !key.is_empty())
+        .ok_or_else(|| "DeepL API key not configured".to_string())?;
+    
+    let client = config.build_client()?;
+    let response = match client.get(format!("{}/usage", deepl_api_base(api_key)))
+        .header("Authorization", format!("DeepL-Auth-Key {}", api_key))
+        .send()
+        .await {
+        Ok(resp) => resp,
+        Err(e) => return Err(format!("Error: Could not connect to DeepL: {}", e)),
+    };
+    
+    if !response.status().is_success() {
+        return Err(format!("Error: DeepL returned status {}", response.status()));
+    }
+    
+    let json: serde_json::Value = response.json().await
+        .map_err(|e| format!("Error: Could not parse DeepL usage: {}", e))?;
+    
+    let characters = json["character_count"].as_u64()
+        .ok_or_else(|| "Error: Unexpected DeepL usage format".to_string())?;
+    
+    Ok(ServiceUsage {
+        characters,
+        limit: json["character_limit"].as_u64(),
+    })
+}